@@ -3,3 +3,17 @@ pub const LP_MINT_SEED: &[u8] = b"LP_MINT";
 pub const VAULT_SEED: &[u8] = b"VAULT_SEED";
 
 pub const AUTH_SEED: &[u8] = b"AUTH_SEED";
+
+/// LP minted to nobody at pool creation, so lp_supply never drops below it
+pub const LOCKED_LP_AMOUNT: u64 = 100;
+
+pub const MINT_ALLOWLIST_SEED: &[u8] = b"MINT_ALLOWLIST";
+
+pub const ADMIN_SEED: &[u8] = b"ADMIN";
+pub const ROLE_REGISTRY_SEED: &[u8] = b"ROLE_REGISTRY";
+
+pub const LOCKED_LP_VAULT_SEED: &[u8] = b"LOCKED_LP_VAULT";
+pub const LOCKED_POSITION_SEED: &[u8] = b"LOCKED_POSITION";
+
+pub const LP_VESTING_SEED: &[u8] = b"LP_VESTING";
+pub const LP_VESTING_VAULT_SEED: &[u8] = b"LP_VESTING_VAULT";
@@ -1,10 +1,12 @@
+use super::{creator_fee, trade_fee};
+
 pub fn swap_base_input_without_fees(
     input_amount: u128,
     input_vault_amount: u128,
     output_vault_amount: u128,
 ) -> Option<u128> {
-    let num = (input_amount as u128).checked_mul(output_vault_amount)?;
-    let den = input_vault_amount.checked_add(input_amount as u128)?;
+    let num = input_amount.checked_mul(output_vault_amount)?;
+    let den = input_vault_amount.checked_add(input_amount)?;
     let output_amount = num.checked_div(den)?;
 
     Some(output_amount)
@@ -28,6 +30,73 @@ pub fn swap_base_output_without_fees(
     Some(input_amount)
 }
 
+/// Part of a single-sided `amount` to swap so that the remainder and the swap
+/// output match the pool ratio afterwards. Binary search over the exact swap
+/// math instead of the closed form, whose square root overflows u128. The
+/// trade and creator fees are rounded up separately, as the swap charges them.
+pub fn single_sided_swap_amount(
+    amount: u128,
+    input_vault_amount: u128,
+    output_vault_amount: u128,
+    trade_fee_rate: u64,
+    input_creator_fee_rate: u64,
+    output_creator_fee_rate: u64,
+) -> Option<u128> {
+    let (mut low, mut high) = (0u128, amount);
+    while low < high {
+        let swap_amount = low + (high - low) / 2;
+        let actual_input = swap_amount
+            .checked_sub(trade_fee(swap_amount, trade_fee_rate)?)?
+            .checked_sub(creator_fee(swap_amount, input_creator_fee_rate)?)?;
+        let output_amount =
+            swap_base_input_without_fees(actual_input, input_vault_amount, output_vault_amount)?;
+        // An output-side creator fee is not deposited
+        let deposit_output_amount =
+            output_amount.checked_sub(creator_fee(output_amount, output_creator_fee_rate)?)?;
+
+        // deposit_output / (output_vault - output) >= remaining / (input_vault + swap_amount)
+        let output_side =
+            deposit_output_amount.checked_mul(input_vault_amount.checked_add(swap_amount)?)?;
+        let input_side =
+            (amount - swap_amount).checked_mul(output_vault_amount.checked_sub(output_amount)?)?;
+        if output_side >= input_side {
+            high = swap_amount;
+        } else {
+            low = swap_amount + 1;
+        }
+    }
+    Some(low)
+}
+
+/// sqrt(reserve_0 * reserve_1), which only grows through fees
+pub fn pool_liquidity(reserve_0: u64, reserve_1: u64) -> u128 {
+    ((reserve_0 as u128) * (reserve_1 as u128)).isqrt()
+}
+
+/// Share of `liquidity` backing `lp_amount` — rounds UP, so a locked
+/// principal is never understated
+pub fn lp_to_liquidity(lp_amount: u64, liquidity: u128, lp_supply: u64) -> Option<u128> {
+    (lp_amount as u128)
+        .checked_mul(liquidity)?
+        .checked_add((lp_supply as u128).checked_sub(1)?)?
+        .checked_div(lp_supply as u128)
+}
+
+/// LP units of a locked position that are fees earned since locking. The
+/// locked liquidity is converted back to LP at today's rate — rounds UP
+pub fn locked_fee_lp_amount(
+    lp_amount: u64,
+    locked_liquidity: u128,
+    liquidity: u128,
+    lp_supply: u64,
+) -> Option<u64> {
+    let principal_lp = locked_liquidity
+        .checked_mul(lp_supply as u128)?
+        .checked_add(liquidity.checked_sub(1)?)?
+        .checked_div(liquidity)?;
+    Some((lp_amount as u128).saturating_sub(principal_lp) as u64)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -38,4 +107,48 @@ mod tests {
         // Expected: (100 * 1000) / (1000 + 100) = 90
         assert_eq!(swap_base_input_without_fees(100, 1000, 1000), Some(90));
     }
+
+    #[test]
+    fn test_single_sided_swap_amount() {
+        // Without fees, zapping 3000 into a 1000/1000 pool swaps 1000 for 500,
+        // leaving 2000 against a 2000/500 pool: both sides are 100% of it
+        let swap_amount = single_sided_swap_amount(3000, 1000, 1000, 0, 0, 0).unwrap();
+        assert_eq!(swap_amount, 1000);
+
+        // A trade fee means slightly more has to be swapped
+        let with_fee = single_sided_swap_amount(3000, 1000, 1000, 2500, 0, 0).unwrap();
+        assert!(with_fee > swap_amount);
+        assert!(with_fee < 3000);
+
+        // and so does a creator fee on either side
+        let trade_fee_only =
+            single_sided_swap_amount(3_000_000, 1_000_000, 1_000_000, 2500, 0, 0).unwrap();
+        let creator_fee_in =
+            single_sided_swap_amount(3_000_000, 1_000_000, 1_000_000, 2500, 1000, 0).unwrap();
+        assert!(creator_fee_in > trade_fee_only);
+        let creator_fee_out =
+            single_sided_swap_amount(3_000_000, 1_000_000, 1_000_000, 2500, 0, 1000).unwrap();
+        assert!(creator_fee_out > trade_fee_only);
+    }
+
+    #[test]
+    fn test_locked_fee_lp_amount() {
+        // 100 of 1000 LP locked in a 1000/1000 pool
+        let locked_liquidity = lp_to_liquidity(100, pool_liquidity(1000, 1000), 1000).unwrap();
+        assert_eq!(locked_liquidity, 100);
+
+        // A pure price move keeps sqrt(k), so nothing is claimable
+        let liquidity = pool_liquidity(4000, 250);
+        assert_eq!(
+            locked_fee_lp_amount(100, locked_liquidity, liquidity, 1000),
+            Some(0)
+        );
+
+        // 10% fee growth: 100 - ceil(100 * 1000 / 1100) = 9 LP of fees
+        let liquidity = pool_liquidity(1100, 1100);
+        assert_eq!(
+            locked_fee_lp_amount(100, locked_liquidity, liquidity, 1000),
+            Some(9)
+        );
+    }
 }
@@ -23,7 +23,116 @@ pub fn protocol_fee(trade_fee: u128, rate: u64) -> Option<u128> {
 pub fn fund_fee(trade_fee: u128, rate: u64) -> Option<u128> {
     floor_div(trade_fee, rate)
 }
-/// Creator fee — rounds UP
+/// Creator fee, charged on the swap amount on top of the trade fee — rounds UP
 pub fn creator_fee(amount: u128, rate: u64) -> Option<u128> {
     ceil_div(amount, rate)
+}
+/// Input needed so that `amount` is left after the trade fee — rounds UP
+/// input_with_fee = amount * denominator / (denominator - fee_rate)
+pub fn amount_with_trade_fee(amount: u128, rate: u64) -> Option<u128> {
+    let den = FEE_RATE_DENOMINATOR.checked_sub(rate)? as u128;
+    let num = amount.checked_mul(FEE_RATE_DENOMINATOR as u128)?;
+    num.checked_add(den.checked_sub(1)?)?.checked_div(den)
+}
+/// Input needed so that `amount` is left after both the trade and creator
+/// fees, each rounded UP on its own — rounds UP
+pub fn amount_with_fees(amount: u128, trade_fee_rate: u64, creator_fee_rate: u64) -> Option<u128> {
+    let mut input = amount_with_trade_fee(amount, trade_fee_rate.checked_add(creator_fee_rate)?)?;
+    // Two ceilings can take one more unit between them than the combined rate
+    loop {
+        let fees =
+            trade_fee(input, trade_fee_rate)?.checked_add(creator_fee(input, creator_fee_rate)?)?;
+        if input.checked_sub(fees).is_some_and(|net| net >= amount) {
+            return Some(input);
+        }
+        input = input.checked_add(1)?;
+    }
+}
+/// Volatility left after decaying linearly to zero over `decay_period` seconds
+pub fn decayed_volatility(volatility: u64, elapsed: u64, decay_period: u64) -> u64 {
+    if elapsed >= decay_period {
+        return 0;
+    }
+    ((volatility as u128) * ((decay_period - elapsed) as u128) / (decay_period as u128)) as u64
+}
+/// Base rate plus `volatility_fee_factor` of the volatility, clamped to [min_rate, max_rate]
+pub fn dynamic_fee_rate(
+    base_rate: u64,
+    volatility: u64,
+    volatility_fee_factor: u64,
+    min_rate: u64,
+    max_rate: u64,
+) -> Option<u64> {
+    let volatility_fee = floor_div(volatility as u128, volatility_fee_factor)?;
+    let rate = (base_rate as u128).saturating_add(volatility_fee);
+    Some(rate.clamp(min_rate as u128, max_rate as u128) as u64)
+}
+/// Relative move of the token 0 price between two reserve states, in ppm
+pub fn price_change_rate(reserves_before: (u64, u64), reserves_after: (u64, u64)) -> u64 {
+    // price_after / price_before = (after_1 * before_0) / (after_0 * before_1)
+    let num = (reserves_after.1 as u128) * (reserves_before.0 as u128);
+    let den = (reserves_after.0 as u128) * (reserves_before.1 as u128);
+    if den == 0 {
+        return 0;
+    }
+    num.abs_diff(den)
+        .checked_mul(FEE_RATE_DENOMINATOR as u128)
+        .map_or(u64::MAX, |change| {
+            u64::try_from(change / den).unwrap_or(u64::MAX)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_change_rate() {
+        // 1000/1000 to 2000/500: token0 drops from 1 to 0.25
+        assert_eq!(price_change_rate((1000, 1000), (2000, 500)), 750_000);
+        assert_eq!(price_change_rate((1000, 1000), (1000, 1000)), 0);
+        assert_eq!(price_change_rate((1000, 1000), (0, 1000)), 0);
+    }
+
+    #[test]
+    fn test_decayed_volatility() {
+        assert_eq!(decayed_volatility(1000, 0, 100), 1000);
+        assert_eq!(decayed_volatility(1000, 50, 100), 500);
+        assert_eq!(decayed_volatility(1000, 100, 100), 0);
+        assert_eq!(decayed_volatility(1000, 500, 100), 0);
+    }
+
+    #[test]
+    fn test_dynamic_fee_rate() {
+        // Base 0.25%, half of the volatility added, bounded to [0.1%, 2%]
+        assert_eq!(dynamic_fee_rate(2500, 0, 500_000, 1000, 20_000), Some(2500));
+        assert_eq!(
+            dynamic_fee_rate(2500, 10_000, 500_000, 1000, 20_000),
+            Some(7500)
+        );
+        assert_eq!(
+            dynamic_fee_rate(2500, u64::MAX, 500_000, 1000, 20_000),
+            Some(20_000)
+        );
+        assert_eq!(dynamic_fee_rate(500, 0, 500_000, 1000, 20_000), Some(1000));
+    }
+
+    #[test]
+    fn test_fee_rises_after_spike_and_decays() {
+        let volatility = price_change_rate((10_000, 10_000), (10_100, 9_901));
+        let spiked = dynamic_fee_rate(2500, volatility, 500_000, 1000, 20_000).unwrap();
+        assert!(spiked > 2500);
+
+        // Halfway through the decay period the surcharge has halved
+        let half = decayed_volatility(volatility, 150, 300);
+        let halfway = dynamic_fee_rate(2500, half, 500_000, 1000, 20_000).unwrap();
+        assert!(halfway > 2500 && halfway < spiked);
+
+        // Fully decayed, back to the base rate
+        let calm = decayed_volatility(volatility, 300, 300);
+        assert_eq!(
+            dynamic_fee_rate(2500, calm, 500_000, 1000, 20_000),
+            Some(2500)
+        );
+    }
 }
\ No newline at end of file
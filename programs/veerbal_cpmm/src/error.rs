@@ -60,5 +60,59 @@ pub enum ErrorCode {
     InvalidFeeReceiver,
 
     #[msg("No Fee to collect")]
-    NoFeesToCollect
+    NoFeesToCollect,
+
+    #[msg("Invalid pool status")]
+    InvalidPoolStatus,
+
+    #[msg("Mint has a permanent delegate")]
+    PermanentDelegateNotAllowed,
+
+    #[msg("Mint is non-transferable")]
+    NonTransferableNotAllowed,
+
+    #[msg("Mint freezes new accounts by default")]
+    DefaultAccountStateFrozen,
+
+    #[msg("Mint transfer hook program is not allowlisted")]
+    TransferHookNotAllowed,
+
+    #[msg("Mint is pausable")]
+    PausableNotAllowed,
+
+    #[msg("Mint extension is not supported")]
+    UnsupportedMintExtension,
+
+    #[msg("Allowlist is full")]
+    AllowlistFull,
+
+    #[msg("Invalid observation window")]
+    InvalidObservationWindow,
+
+    #[msg("Not enough oracle history for this window")]
+    OracleNotReady,
+
+    #[msg("Invalid swap route")]
+    InvalidRoute,
+
+    #[msg("Invalid dynamic fee parameters")]
+    InvalidDynamicFee,
+
+    #[msg("Role has too many members")]
+    RoleFull,
+
+    #[msg("Pool still has liquidity or uncollected fees")]
+    PoolNotEmpty,
+
+    #[msg("Invalid vesting schedule")]
+    InvalidVestingSchedule,
+
+    #[msg("No vested LP to claim")]
+    NoVestedLp,
+
+    #[msg("Pool fee overrides are invalid with the current config rates")]
+    InvalidPoolFeeRates,
+
+    #[msg("Vesting escrow still holds unclaimed LP")]
+    LpVestingNotClaimed,
 }
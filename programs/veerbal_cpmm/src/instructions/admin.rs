@@ -0,0 +1,111 @@
+use crate::{
+    constants::{ADMIN_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    states::{AdminState, AdminUpdatedEvent, Role, RoleRegistry, RoleUpdatedEvent},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct InitializeAdmin<'info> {
+    #[account(mut, address = crate::ADMIN @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(init, seeds=[ADMIN_SEED], bump, payer = owner, space = AdminState::LEN)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(init, seeds=[ROLE_REGISTRY_SEED], bump, payer = owner, space = RoleRegistry::LEN)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ProposeAdmin<'info> {
+    #[account(address = admin_state.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptAdmin<'info> {
+    #[account(address = admin_state.pending_admin @ ErrorCode::InvalidOwner)]
+    pub new_admin: Signer<'info>,
+
+    #[account(mut, seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateRole<'info> {
+    #[account(address = admin_state.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(mut, seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+}
+
+/// One-off setup by the deploy-time key, which becomes the first admin
+pub fn initialize_admin(ctx: Context<InitializeAdmin>) -> Result<()> {
+    let admin_state = &mut ctx.accounts.admin_state;
+    admin_state.bump = ctx.bumps.admin_state;
+    admin_state.admin = ctx.accounts.owner.key();
+    admin_state.pending_admin = Pubkey::default();
+    ctx.accounts.role_registry.bump = ctx.bumps.role_registry;
+
+    emit!(AdminUpdatedEvent {
+        admin: admin_state.admin,
+        pending_admin: admin_state.pending_admin,
+    });
+
+    Ok(())
+}
+
+/// Nominate a new admin, who must accept before the role moves.
+/// Proposing the default pubkey cancels a pending transfer
+pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+    let admin_state = &mut ctx.accounts.admin_state;
+    admin_state.pending_admin = new_admin;
+
+    emit!(AdminUpdatedEvent {
+        admin: admin_state.admin,
+        pending_admin: admin_state.pending_admin,
+    });
+
+    Ok(())
+}
+
+pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+    let admin_state = &mut ctx.accounts.admin_state;
+    admin_state.admin = admin_state.pending_admin;
+    admin_state.pending_admin = Pubkey::default();
+
+    emit!(AdminUpdatedEvent {
+        admin: admin_state.admin,
+        pending_admin: admin_state.pending_admin,
+    });
+
+    Ok(())
+}
+
+/// Grant or revoke a role, only the admin manages roles
+pub fn update_role(
+    ctx: Context<UpdateRole>,
+    role: Role,
+    member: Pubkey,
+    granted: bool,
+) -> Result<()> {
+    ctx.accounts.role_registry.set_role(role, member, granted)?;
+
+    emit!(RoleUpdatedEvent {
+        role,
+        member,
+        granted,
+    });
+
+    Ok(())
+}
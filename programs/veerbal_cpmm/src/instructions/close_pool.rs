@@ -0,0 +1,133 @@
+use crate::{
+    constants::{ADMIN_SEED, AUTH_SEED, LOCKED_LP_AMOUNT, POOL_SEED, VAULT_SEED},
+    error::ErrorCode,
+    states::{AdminState, AmmConfig, PoolClosedEvent, PoolState},
+    utils::{close_pool_vault, transfer_from_pool_vault_to_user},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+#[derive(Accounts)]
+pub struct ClosePool<'info> {
+    #[account(mut, constraint = owner.key() == pool_state.pool_creator || owner.key() == admin_state.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut, close = pool_creator, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), token_0_mint.key().as_ref(), token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    /// CHECK: Rent goes back to the pool creator, who paid it
+    #[account(mut, address = pool_state.pool_creator @ ErrorCode::InvalidOwner)]
+    pub pool_creator: UncheckedAccount<'info>,
+
+    // Mutable so withheld transfer fees can be harvested before the vaults close
+    #[account(mut, address = pool_state.token_0_mint, mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, address = pool_state.token_1_mint, mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_0_mint.key().as_ref()], bump = pool_state.token_0_bump)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_1_mint.key().as_ref()], bump = pool_state.token_1_bump)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    /// CHECK: Only used as the owner of the dust receiver accounts
+    #[account(address = amm_config.protocol_owner @ ErrorCode::InvalidFeeReceiver)]
+    pub protocol_owner: UncheckedAccount<'info>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = token_0_mint, associated_token::authority = protocol_owner, associated_token::token_program = token_0_program)]
+    pub protocol_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = owner, associated_token::mint = token_1_mint, associated_token::authority = protocol_owner, associated_token::token_program = token_1_program)]
+    pub protocol_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.token_0_program)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+    #[account(address = pool_state.token_1_program)]
+    pub token_1_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Close a pool whose only liquidity left is the locked LP and whose fees
+/// have all been collected. Vault dust goes to the protocol owner and the
+/// rent of the vaults and pool state back to the pool creator. The LP mint
+/// lives under the legacy token program, which cannot close mints, so it
+/// stays and the pool cannot be created again under the same config
+pub fn close_pool<'info>(ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>) -> Result<()> {
+    let pool_state = &ctx.accounts.pool_state;
+    require!(
+        pool_state.lp_supply <= LOCKED_LP_AMOUNT
+            && pool_state.protocol_token_0_fee == 0
+            && pool_state.protocol_token_1_fee == 0
+            && pool_state.fund_token_0_fee == 0
+            && pool_state.fund_token_1_fee == 0
+            && pool_state.creator_token_0_fee == 0
+            && pool_state.creator_token_1_fee == 0,
+        ErrorCode::PoolNotEmpty
+    );
+
+    let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+
+    let amount_0 = ctx.accounts.token_0_vault.amount;
+    let amount_1 = ctx.accounts.token_1_vault.amount;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.protocol_token_0_account.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        amount_0,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.protocol_token_1_account.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        amount_1,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    close_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.pool_creator.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        signer_seeds,
+    )?;
+    close_pool_vault(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.pool_creator.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        signer_seeds,
+    )?;
+
+    emit!(PoolClosedEvent {
+        pool_id: pool_state.key(),
+        closed_by: ctx.accounts.owner.key(),
+        amount_0,
+        amount_1,
+    });
+
+    Ok(())
+}
@@ -1,18 +1,19 @@
 use crate::{
     constants::{AUTH_SEED, POOL_SEED, VAULT_SEED},
     error::ErrorCode,
-    states::{AmmConfig, PoolState},
+    states::{AmmConfig, FeeCollectedEvent, FeeType, PoolState},
+    utils::transfer_from_pool_vault_to_user,
 };
 use anchor_lang::prelude::*;
 use anchor_spl::associated_token::AssociatedToken;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
 #[derive(Accounts)]
 pub struct CollectFundFee<'info> {
-    #[account(address = pool_state.token_0_mint)]
-    pub token_0_mint: Account<'info, Mint>,
+    #[account(address = pool_state.token_0_mint, mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(address = pool_state.token_1_mint)]
-    pub token_1_mint: Account<'info, Mint>,
+    #[account(address = pool_state.token_1_mint, mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), token_0_mint.key().as_ref(), token_1_mint.key().as_ref()], bump = pool_state.bump)]
     pub pool_state: Box<Account<'info, PoolState>>,
@@ -21,30 +22,35 @@ pub struct CollectFundFee<'info> {
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
     #[account(mut, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_0_mint.key().as_ref()], bump = pool_state.token_0_bump)]
-    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_1_mint.key().as_ref()], bump = pool_state.token_1_bump)]
-    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     /// CHECKED - No deserialization
     #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
     pub authority: UncheckedAccount<'info>,
 
-    #[account(mut, associated_token::mint = token_0_mint, associated_token::authority = owner)]
-    pub receiver_token_0_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, associated_token::mint = token_0_mint, associated_token::authority = owner, associated_token::token_program = token_0_program)]
+    pub receiver_token_0_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, associated_token::mint = token_1_mint, associated_token::authority = owner)]
-    pub receiver_token_1_account: Box<Account<'info, TokenAccount>>,
+    #[account(mut, associated_token::mint = token_1_mint, associated_token::authority = owner, associated_token::token_program = token_1_program)]
+    pub receiver_token_1_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, address = amm_config.fund_owner @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
-    pub token_program: Program<'info, Token>,
+    #[account(address = pool_state.token_0_program)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+    #[account(address = pool_state.token_1_program)]
+    pub token_1_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn collect_fund_fee(ctx: Context<CollectFundFee>) -> Result<()> {
+pub fn collect_fund_fee<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CollectFundFee<'info>>,
+) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
 
     let fee_0 = pool_state.fund_token_0_fee;
@@ -56,36 +62,30 @@ pub fn collect_fund_fee(ctx: Context<CollectFundFee>) -> Result<()> {
     let signer_seeds = &[&seeds[..]];
 
     if fee_0 > 0 {
-        let accounts = Transfer {
-            from: ctx.accounts.token_0_vault.to_account_info(),
-            to: ctx.accounts.receiver_token_0_account.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            accounts,
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_0_vault.to_account_info(),
+            ctx.accounts.receiver_token_0_account.to_account_info(),
+            &ctx.accounts.token_0_mint,
+            ctx.accounts.token_0_program.to_account_info(),
+            fee_0,
             signer_seeds,
-        );
-
-        token::transfer(cpi_context, fee_0)?
+            ctx.remaining_accounts,
+        )?;
     }
 
     // 5. Transfer token_1 fees (if any)
     if fee_1 > 0 {
-        let accounts = Transfer {
-            from: ctx.accounts.token_1_vault.to_account_info(),
-            to: ctx.accounts.receiver_token_1_account.to_account_info(),
-            authority: ctx.accounts.authority.to_account_info(),
-        };
-
-        let cpi_context = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            accounts,
+        transfer_from_pool_vault_to_user(
+            ctx.accounts.authority.to_account_info(),
+            ctx.accounts.token_1_vault.to_account_info(),
+            ctx.accounts.receiver_token_1_account.to_account_info(),
+            &ctx.accounts.token_1_mint,
+            ctx.accounts.token_1_program.to_account_info(),
+            fee_1,
             signer_seeds,
-        );
-
-        token::transfer(cpi_context, fee_1)?
+            ctx.remaining_accounts,
+        )?;
     }
 
     // 6. Reset fee counters
@@ -94,5 +94,13 @@ pub fn collect_fund_fee(ctx: Context<CollectFundFee>) -> Result<()> {
 
     // 7. Update recent_epoch
     pool_state.recent_epoch = Clock::get()?.epoch;
+
+    emit!(FeeCollectedEvent {
+        pool_id: pool_state.key(),
+        fee_type: FeeType::Fund,
+        receiver: ctx.accounts.owner.key(),
+        amount_0: fee_0,
+        amount_1: fee_1,
+    });
     Ok(())
 }
@@ -0,0 +1,204 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{
+        ADMIN_SEED, AUTH_SEED, LOCKED_LP_VAULT_SEED, LOCKED_POSITION_SEED, LP_MINT_SEED, POOL_SEED,
+    },
+    curve::{locked_fee_lp_amount, pool_liquidity},
+    error::ErrorCode,
+    states::{
+        AdminState, AmmConfig, FeeCollectedEvent, FeeType, LockedPosition, LpChangeEvent,
+        LpChangeType, PoolState, PoolStatusBitIndex,
+    },
+    utils::{get_transfer_fee, transfer_from_pool_vault_to_user},
+};
+
+#[derive(Accounts)]
+pub struct CollectLockedFee<'info> {
+    #[account(address = locked_position.owner @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(mut, seeds=[LOCKED_POSITION_SEED, pool_state.key().as_ref(), owner.key().as_ref()], bump = locked_position.bump)]
+    pub locked_position: Box<Account<'info, LockedPosition>>,
+
+    #[account(mut, seeds=[LOCKED_LP_VAULT_SEED, pool_state.key().as_ref()], bump)]
+    pub locked_lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = pool_state.token_0_mint @ ErrorCode::MintMismatch, mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = pool_state.token_1_mint @ ErrorCode::MintMismatch, mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, address = pool_state.token_0_vault @ ErrorCode::InvalidVault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, address = pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_0_mint, token::authority = owner, token::token_program = token_0_program)]
+    pub owner_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = token_1_mint, token::authority = owner, token::token_program = token_1_program)]
+    pub owner_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    #[account(address = pool_state.token_0_program)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+    #[account(address = pool_state.token_1_program)]
+    pub token_1_program: Interface<'info, TokenInterface>,
+}
+
+/// Pay out the fees earned by a locked position. The LP they correspond to
+/// is burned from the locked LP vault and its share of the reserves sent to
+/// the owner, the locked sqrt(k) share stays put
+pub fn collect_locked_fee<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CollectLockedFee<'info>>,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
+
+    // Taking fees out is a withdrawal of the fee share
+    require!(
+        pool_state.is_enabled(PoolStatusBitIndex::Withdraw)
+            && amm_config.is_enabled(PoolStatusBitIndex::Withdraw)
+            && admin_state.is_enabled(PoolStatusBitIndex::Withdraw),
+        ErrorCode::WithdrawDisabled
+    );
+
+    let (clean_vault_0, clean_vault_1) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+
+    let locked_position = &mut ctx.accounts.locked_position;
+    let fee_lp_amount = locked_fee_lp_amount(
+        locked_position.lp_amount,
+        locked_position.locked_liquidity,
+        pool_liquidity(clean_vault_0, clean_vault_1),
+        pool_state.lp_supply,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    require!(fee_lp_amount > 0, ErrorCode::NoFeesToCollect);
+
+    // Same pro-rata math as a withdrawal (round DOWN)
+    let token_0_amount: u64 = (fee_lp_amount as u128)
+        .checked_mul(clean_vault_0 as u128)
+        .and_then(|amount| amount.checked_div(pool_state.lp_supply as u128))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let token_1_amount: u64 = (fee_lp_amount as u128)
+        .checked_mul(clean_vault_1 as u128)
+        .and_then(|amount| amount.checked_div(pool_state.lp_supply as u128))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_sub(fee_lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    locked_position.lp_amount = locked_position
+        .lp_amount
+        .checked_sub(fee_lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.lp_mint.to_account_info(),
+                from: ctx.accounts.locked_lp_vault.to_account_info(),
+                authority: ctx.accounts.authority.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        fee_lp_amount,
+    )?;
+
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.owner_token_0.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        token_0_amount,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.owner_token_1.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        token_1_amount,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    emit!(FeeCollectedEvent {
+        pool_id: pool_state.key(),
+        fee_type: FeeType::LockedLiquidity,
+        receiver: ctx.accounts.owner.key(),
+        amount_0: token_0_amount,
+        amount_1: token_1_amount,
+    });
+    // The burn changes supply and reserves like a withdrawal does
+    let token_0_transfer_fee = get_transfer_fee(&ctx.accounts.token_0_mint, token_0_amount)?;
+    let token_1_transfer_fee = get_transfer_fee(&ctx.accounts.token_1_mint, token_1_amount)?;
+    let reserve_0_after = clean_vault_0
+        .checked_sub(token_0_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let reserve_1_after = clean_vault_1
+        .checked_sub(token_1_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool_state.update_oracle(
+        reserve_0_after,
+        reserve_1_after,
+        Clock::get()?.unix_timestamp as u64,
+    );
+    emit!(LpChangeEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.owner.key(),
+        change_type: LpChangeType::Withdraw,
+        lp_amount: fee_lp_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use crate::{error::ErrorCode, AmmConfig};
+use crate::{
+    constants::{ADMIN_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    states::{AdminState, ConfigCreatedEvent, Role, RoleRegistry},
+    AmmConfig,
+};
 use anchor_lang::prelude::*;
 
 pub const CONFIG_SEED: &[u8] = b"AMM_CONFIG";
@@ -6,9 +11,15 @@ pub const CONFIG_SEED: &[u8] = b"AMM_CONFIG";
 #[derive(Accounts)]
 #[instruction(index: u16)]
 pub struct CreateAmmConfig<'info> {
-    #[account(mut, address = crate::ADMIN @ ErrorCode::InvalidOwner)]
+    #[account(mut, constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::ConfigCreator, &owner.key()) @ ErrorCode::InvalidOwner)]
     pub owner: Signer<'info>,
 
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
     #[account(init, seeds=[CONFIG_SEED, index.to_be_bytes().as_ref()], bump, payer = owner, space = AmmConfig::LEN)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
@@ -24,16 +35,6 @@ pub fn create_amm_config(
     fund_fee_rate: u64,
     create_pool_fee: u64,
 ) -> Result<()> {
-    require!(
-        trade_fee_rate + creator_fee_rate < 1_000_000,
-        ErrorCode::FeeExceedHundredPercentage
-    );
-
-    require!(
-        protocol_fee_rate + fund_fee_rate < 1_000_000,
-        ErrorCode::FeeExceedHundredPercentage
-    );
-
     let amm_config = &mut ctx.accounts.amm_config;
     amm_config.bump = ctx.bumps.amm_config;
     amm_config.index = index;
@@ -43,10 +44,27 @@ pub fn create_amm_config(
     amm_config.protocol_fee_rate = protocol_fee_rate;
     amm_config.fund_fee_rate = fund_fee_rate;
     amm_config.trade_fee_rate = trade_fee_rate;
-    amm_config.protocol_owner = ctx.accounts.owner.key();
-    amm_config.fund_owner = ctx.accounts.owner.key();
+    // Fee receivers start with the admin, a config creator gets no claim on fees
+    amm_config.protocol_owner = ctx.accounts.admin_state.admin;
+    amm_config.fund_owner = ctx.accounts.admin_state.admin;
 
     amm_config.disable_create_pool = false;
+    amm_config.dynamic_fee_enabled = false;
+    amm_config.status = 0;
+
+    amm_config.check_fee_rates()?;
+
+    emit!(ConfigCreatedEvent {
+        amm_config: amm_config.key(),
+        index,
+        trade_fee_rate,
+        creator_fee_rate,
+        protocol_fee_rate,
+        fund_fee_rate,
+        create_pool_fee,
+        protocol_owner: amm_config.protocol_owner,
+        fund_owner: amm_config.fund_owner,
+    });
 
     Ok(())
 }
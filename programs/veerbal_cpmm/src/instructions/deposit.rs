@@ -1,14 +1,19 @@
 use anchor_lang::prelude::*;
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+    token::Token,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
 use crate::{
-    constants::{AUTH_SEED, LP_MINT_SEED, POOL_SEED},
+    constants::{ADMIN_SEED, AUTH_SEED, LP_MINT_SEED, POOL_SEED},
     error::ErrorCode,
     instructions::CONFIG_SEED,
-    states::{PoolState, PoolStatusBitIndex},
+    states::{AdminState, LpChangeEvent, LpChangeType, PoolState, PoolStatusBitIndex},
+    utils::{
+        get_transfer_fee, get_transfer_inverse_fee, is_native_mint,
+        transfer_from_user_to_pool_vault, unwrap_native_sol, wrap_native_sol,
+    },
     AmmConfig,
 };
 
@@ -23,54 +28,73 @@ pub struct Deposit<'info> {
     #[account(mut, seeds=[POOL_SEED, amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
     pub pool_state: Box<Account<'info, PoolState>>,
 
-    // User will provide these accounts
-    #[account(mut, token::mint = pool_state.token_0_mint, token::authority = signer)]
-    pub signer_token_0: Box<Account<'info, TokenAccount>>,
+    #[account(address = pool_state.token_0_mint @ ErrorCode::MintMismatch, mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut, token::mint = pool_state.token_1_mint, token::authority = signer)]
-    pub signer_token_1: Box<Account<'info, TokenAccount>>,
+    #[account(address = pool_state.token_1_mint @ ErrorCode::MintMismatch, mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // User token accounts, created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = token_0_mint, associated_token::authority = signer, associated_token::token_program = token_0_program)]
+    pub signer_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = signer, associated_token::mint = token_1_mint, associated_token::authority = signer, associated_token::token_program = token_1_program)]
+    pub signer_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, constraint = token_0_vault.key() == pool_state.token_0_vault @ ErrorCode::InvalidVault, token::mint = pool_state.token_0_mint, token::authority = authority)]
-    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, constraint = token_1_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, token::mint = pool_state.token_1_mint, token::authority = authority)]
-    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(init_if_needed, associated_token::mint = lp_mint, associated_token::authority = signer, payer = signer)]
-    pub signer_lp: Box<Account<'info, TokenAccount>>,
+    #[account(init_if_needed, associated_token::mint = lp_mint, associated_token::authority = signer, associated_token::token_program = token_program, payer = signer)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     // pool state dependent upon amm config
     #[account(seeds=[CONFIG_SEED, amm_config.index.to_be_bytes().as_ref()], bump = amm_config.bump)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
     // Authority pda is needed to sign vault transactions
     /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
     pub authority: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
+    #[account(address = pool_state.token_0_program)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+    #[account(address = pool_state.token_1_program)]
+    pub token_1_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
     pub system_program: Program<'info, System>,
 }
 
-pub fn deposit(
-    ctx: Context<Deposit>,
+pub fn deposit<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
     lp_amount: u64,
     maximum_token_0_amount: u64,
     maximum_token_1_amount: u64,
+    native_sol: bool,
 ) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
 
     require!(
-        pool_state.is_enabled(PoolStatusBitIndex::Deposit),
+        pool_state.is_enabled(PoolStatusBitIndex::Deposit)
+            && amm_config.is_enabled(PoolStatusBitIndex::Deposit)
+            && admin_state.is_enabled(PoolStatusBitIndex::Deposit),
         ErrorCode::DepositDisabled
     );
 
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
     require!(
-        pool_state.open_time < Clock::get()?.unix_timestamp as u64,
+        pool_state.open_time < block_timestamp,
         ErrorCode::DepositDisabled
     );
 
@@ -91,7 +115,7 @@ pub fn deposit(
     let num = (lp_amount as u128)
         .checked_mul(clean_vault_0 as u128)
         .ok_or(ErrorCode::MathOverflow)?;
-    let token_0_amount = num
+    let token_0_amount: u64 = num
         .div_ceil(lp_supply as u128)
         .try_into()
         .map_err(|_| ErrorCode::MathOverflow)?;
@@ -99,7 +123,7 @@ pub fn deposit(
     let num = (lp_amount as u128)
         .checked_mul(clean_vault_1 as u128)
         .ok_or(ErrorCode::MathOverflow)?;
-    let token_1_amount = num
+    let token_1_amount: u64 = num
         .div_ceil(lp_supply as u128)
         .try_into()
         .map_err(|_| ErrorCode::MathOverflow)?;
@@ -107,34 +131,86 @@ pub fn deposit(
     require!(token_0_amount > 0, ErrorCode::ZeroTradingTokens);
     require!(token_1_amount > 0, ErrorCode::ZeroTradingTokens);
 
+    // Vaults must receive the full amounts, so the user also covers transfer fees
+    let token_0_amount_with_fee = token_0_amount
+        .checked_add(get_transfer_inverse_fee(
+            &ctx.accounts.token_0_mint,
+            token_0_amount,
+        )?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let token_1_amount_with_fee = token_1_amount
+        .checked_add(get_transfer_inverse_fee(
+            &ctx.accounts.token_1_mint,
+            token_1_amount,
+        )?)
+        .ok_or(ErrorCode::MathOverflow)?;
+
     require!(
-        token_0_amount <= maximum_token_0_amount,
+        token_0_amount_with_fee <= maximum_token_0_amount,
         ErrorCode::MaximumAmountExceed
     );
     require!(
-        token_1_amount <= maximum_token_1_amount,
+        token_1_amount_with_fee <= maximum_token_1_amount,
         ErrorCode::MaximumAmountExceed
     );
 
-    let accounts_0 = Transfer {
-        from: ctx.accounts.signer_token_0.to_account_info(),
-        to: ctx.accounts.token_0_vault.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
-    };
-
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let cpi = CpiContext::new(token_program, accounts_0);
-    token::transfer(cpi, token_0_amount)?;
+    let is_native_0 = native_sol && is_native_mint(&ctx.accounts.token_0_mint.key());
+    let is_native_1 = native_sol && is_native_mint(&ctx.accounts.token_1_mint.key());
+    if is_native_0 {
+        wrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_0.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_0_program.to_account_info(),
+            token_0_amount_with_fee,
+        )?;
+    }
+    if is_native_1 {
+        wrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_1.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.token_1_program.to_account_info(),
+            token_1_amount_with_fee,
+        )?;
+    }
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer_token_0.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        token_0_amount_with_fee,
+        ctx.remaining_accounts,
+    )?;
 
-    let accounts_1 = Transfer {
-        from: ctx.accounts.signer_token_1.to_account_info(),
-        to: ctx.accounts.token_1_vault.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
-    };
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer_token_1.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        token_1_amount_with_fee,
+        ctx.remaining_accounts,
+    )?;
 
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let cpi = CpiContext::new(token_program, accounts_1);
-    token::transfer(cpi, token_1_amount)?;
+    if is_native_0 {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_0.to_account_info(),
+            ctx.accounts.token_0_program.to_account_info(),
+            ctx.accounts.signer_token_0.amount,
+        )?;
+    }
+    if is_native_1 {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_1.to_account_info(),
+            ctx.accounts.token_1_program.to_account_info(),
+            ctx.accounts.signer_token_1.amount,
+        )?;
+    }
 
     let mint_accounts = MintTo {
         mint: ctx.accounts.lp_mint.to_account_info(),
@@ -152,9 +228,84 @@ pub fn deposit(
         .ok_or(ErrorCode::MathOverflow)?;
 
     let cpi_ctx = CpiContext::new_with_signer(token_program, mint_accounts, signer_seeds);
-    token::mint_to(cpi_ctx, lp_amount)?;
+    token_interface::mint_to(cpi_ctx, lp_amount)?;
 
     pool_state.recent_epoch = Clock::get()?.epoch;
+    let reserve_0_after = clean_vault_0
+        .checked_add(token_0_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let reserve_1_after = clean_vault_1
+        .checked_add(token_1_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+
+    emit!(LpChangeEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        change_type: LpChangeType::Deposit,
+        lp_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee: token_0_amount_with_fee - token_0_amount,
+        token_1_transfer_fee: token_1_amount_with_fee - token_1_amount,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
 
     Ok(())
 }
+
+/// Deposit an exact amount of one token. LP is rounded down from what the
+/// vault receives, then `deposit` pulls the other side rounded up.
+pub fn deposit_base_input<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+    is_token_0: bool,
+    amount: u64,
+    maximum_other_amount: u64,
+    minimum_lp_amount: u64,
+    native_sol: bool,
+) -> Result<()> {
+    require!(amount > 0, ErrorCode::InvalidTokenAmount);
+    let pool_state = &ctx.accounts.pool_state;
+    require!(pool_state.lp_supply > 0, ErrorCode::PoolNotInitialized);
+
+    let (clean_vault_0, clean_vault_1) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    let (base_mint, base_vault_amount) = if is_token_0 {
+        (&ctx.accounts.token_0_mint, clean_vault_0)
+    } else {
+        (&ctx.accounts.token_1_mint, clean_vault_1)
+    };
+
+    let vault_amount_in = amount
+        .checked_sub(get_transfer_fee(base_mint, amount)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let lp_amount: u64 = (vault_amount_in as u128)
+        .checked_mul(pool_state.lp_supply as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(base_vault_amount as u128)
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+    require!(lp_amount > 0, ErrorCode::InvalidLPAmount);
+    require!(lp_amount >= minimum_lp_amount, ErrorCode::SlippageExceeded);
+
+    let (maximum_token_0_amount, maximum_token_1_amount) = if is_token_0 {
+        (amount, maximum_other_amount)
+    } else {
+        (maximum_other_amount, amount)
+    };
+    deposit(
+        ctx,
+        lp_amount,
+        maximum_token_0_amount,
+        maximum_token_1_amount,
+        native_sol,
+    )
+}
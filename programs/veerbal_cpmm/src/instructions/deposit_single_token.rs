@@ -0,0 +1,319 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ADMIN_SEED, AUTH_SEED, LP_MINT_SEED, POOL_SEED},
+    curve::{
+        creator_fee, fund_fee, protocol_fee, single_sided_swap_amount,
+        swap_base_input_without_fees, trade_fee,
+    },
+    error::ErrorCode,
+    states::{AdminState, LpChangeEvent, LpChangeType, PoolState, PoolStatusBitIndex},
+    utils::{
+        get_transfer_fee, is_native_mint, transfer_from_user_to_pool_vault, unwrap_native_sol,
+        wrap_native_sol,
+    },
+    AmmConfig,
+};
+
+#[derive(Accounts)]
+pub struct DepositSingleToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(address = input_vault.mint @ ErrorCode::MintMismatch, mint::token_program = input_token_program)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = input_token_mint, associated_token::authority = signer, associated_token::token_program = input_token_program)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, constraint = input_vault.key() == pool_state.token_0_vault || input_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only read for its reserve, nothing leaves this vault
+    #[account(constraint = output_vault.key() == pool_state.token_0_vault || output_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, constraint = output_vault.key() != input_vault.key() @ ErrorCode::SameVault)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, associated_token::mint = lp_mint, associated_token::authority = signer, associated_token::token_program = token_program, payer = signer)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Zap in: swap part of a single token through the pool and deposit the rest
+/// alongside the swap output, which never leaves the vault
+pub fn deposit_single_token<'info>(
+    ctx: Context<'_, '_, 'info, 'info, DepositSingleToken<'info>>,
+    amount_in: u64,
+    minimum_lp_amount: u64,
+    native_sol: bool,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
+
+    require!(
+        pool_state.is_enabled(PoolStatusBitIndex::Deposit)
+            && amm_config.is_enabled(PoolStatusBitIndex::Deposit)
+            && admin_state.is_enabled(PoolStatusBitIndex::Deposit),
+        ErrorCode::DepositDisabled
+    );
+    // The swap leg trades against the pool, so a swap pause applies too
+    require!(
+        pool_state.is_enabled(PoolStatusBitIndex::Swap)
+            && amm_config.is_enabled(PoolStatusBitIndex::Swap)
+            && admin_state.is_enabled(PoolStatusBitIndex::Swap),
+        ErrorCode::SwapBlocked
+    );
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    require!(
+        pool_state.open_time < block_timestamp,
+        ErrorCode::DepositDisabled
+    );
+
+    require!(amount_in > 0, ErrorCode::InvalidTokenAmount);
+    require!(pool_state.lp_supply > 0, ErrorCode::PoolNotInitialized);
+
+    let is_token_0_input = ctx.accounts.input_vault.key() == pool_state.token_0_vault;
+    let (vault_0_amount, vault_1_amount) = if is_token_0_input {
+        (
+            ctx.accounts.input_vault.amount,
+            ctx.accounts.output_vault.amount,
+        )
+    } else {
+        (
+            ctx.accounts.output_vault.amount,
+            ctx.accounts.input_vault.amount,
+        )
+    };
+    let (clean_vault_0, clean_vault_1) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+    let (input_vault_balance, output_vault_balance) = if is_token_0_input {
+        (clean_vault_0 as u128, clean_vault_1 as u128)
+    } else {
+        (clean_vault_1 as u128, clean_vault_0 as u128)
+    };
+
+    // The vault only receives what is left after the input transfer fee
+    let transfer_fee_in = get_transfer_fee(&ctx.accounts.input_token_mint, amount_in)?;
+    let vault_amount_in = amount_in
+        .checked_sub(transfer_fee_in)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(vault_amount_in > 0, ErrorCode::InvalidTokenAmount);
+
+    // Swap leg: same fee and curve math as a base input swap
+    let trade_fee_rate = pool_state.trade_fee_rate(amm_config, block_timestamp)?;
+    // The creator fee is a separate surcharge on the input or the output
+    let (input_creator_fee_rate, output_creator_fee_rate) =
+        pool_state.creator_fee_rates(amm_config, is_token_0_input);
+    let swap_amount = single_sided_swap_amount(
+        vault_amount_in as u128,
+        input_vault_balance,
+        output_vault_balance,
+        trade_fee_rate,
+        input_creator_fee_rate,
+        output_creator_fee_rate,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+    let fee = trade_fee(swap_amount, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let protocol_fee_amount = protocol_fee(fee, pool_state.protocol_fee_rate(amm_config))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let fund_fee_amount =
+        fund_fee(fee, pool_state.fund_fee_rate(amm_config)).ok_or(ErrorCode::MathOverflow)?;
+    let creator_fee_in =
+        creator_fee(swap_amount, input_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let actual_input = swap_amount
+        .checked_sub(fee)
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let output_amount =
+        swap_base_input_without_fees(actual_input, input_vault_balance, output_vault_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+    let creator_fee_out =
+        creator_fee(output_amount, output_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    // Only what is left after an output-side creator fee is deposited
+    let deposit_output_amount = output_amount
+        .checked_sub(creator_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(deposit_output_amount > 0, ErrorCode::ZeroTradingTokens);
+    let (creator_fee_in, creator_fee_out) =
+        pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
+
+    pool_state.accrue_fees(
+        is_token_0_input,
+        u64::try_from(protocol_fee_amount).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(fund_fee_amount).map_err(|_| ErrorCode::MathOverflow)?,
+        u64::try_from(creator_fee_in).map_err(|_| ErrorCode::MathOverflow)?,
+    )?;
+    pool_state.accrue_fees(
+        !is_token_0_input,
+        0,
+        0,
+        u64::try_from(creator_fee_out).map_err(|_| ErrorCode::MathOverflow)?,
+    )?;
+
+    // Reserves after the swap leg; the LP share of the fee stays in the pool
+    let input_reserve = input_vault_balance
+        .checked_add(swap_amount)
+        .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+        .and_then(|amount| amount.checked_sub(fund_fee_amount))
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let output_reserve = output_vault_balance
+        .checked_sub(output_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    // Only the swap leg moves the price, the deposit leg is pro-rata
+    let input_reserve_u64 = u64::try_from(input_reserve).map_err(|_| ErrorCode::MathOverflow)?;
+    let output_reserve_u64 = u64::try_from(output_reserve).map_err(|_| ErrorCode::MathOverflow)?;
+    let (swap_reserve_0, swap_reserve_1) = if is_token_0_input {
+        (input_reserve_u64, output_reserve_u64)
+    } else {
+        (output_reserve_u64, input_reserve_u64)
+    };
+    pool_state.update_volatility(
+        &ctx.accounts.amm_config,
+        (clean_vault_0, clean_vault_1),
+        (swap_reserve_0, swap_reserve_1),
+        block_timestamp,
+    );
+
+    // Deposit leg: mint for the scarcer side, any dust stays with the LPs (round DOWN)
+    let deposit_amount = (vault_amount_in as u128)
+        .checked_sub(swap_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let lp_supply = pool_state.lp_supply as u128;
+    let lp_from_input = deposit_amount
+        .checked_mul(lp_supply)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(input_reserve)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let lp_from_output = deposit_output_amount
+        .checked_mul(lp_supply)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(output_reserve)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let lp_amount: u64 = lp_from_input
+        .min(lp_from_output)
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+    require!(lp_amount > 0, ErrorCode::InvalidLPAmount);
+    require!(lp_amount >= minimum_lp_amount, ErrorCode::SlippageExceeded);
+
+    let is_native_input = native_sol && is_native_mint(&ctx.accounts.input_token_mint.key());
+    if is_native_input {
+        wrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            amount_in,
+        )?;
+    }
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        &ctx.accounts.input_token_mint,
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.remaining_accounts,
+    )?;
+
+    if is_native_input {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            ctx.accounts.input_token_account.amount,
+        )?;
+    }
+
+    let mint_accounts = MintTo {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        to: ctx.accounts.signer_lp.to_account_info(),
+        authority: ctx.accounts.authority.to_account_info(),
+    };
+
+    let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+    let token_program = ctx.accounts.token_program.to_account_info();
+
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_add(lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let cpi_ctx = CpiContext::new_with_signer(token_program, mint_accounts, signer_seeds);
+    token_interface::mint_to(cpi_ctx, lp_amount)?;
+
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    // Net effect on the vaults: the whole input lands on one side, only an
+    // output-side creator fee leaves the other
+    let input_reserve_after: u64 = input_reserve
+        .checked_add(deposit_amount)
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let output_reserve_after: u64 = output_vault_balance
+        .checked_sub(creator_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let (token_0_amount, token_1_amount, token_0_transfer_fee, token_1_transfer_fee) =
+        if is_token_0_input {
+            (vault_amount_in, 0, transfer_fee_in, 0)
+        } else {
+            (0, vault_amount_in, 0, transfer_fee_in)
+        };
+    let (reserve_0_after, reserve_1_after) = if is_token_0_input {
+        (input_reserve_after, output_reserve_after)
+    } else {
+        (output_reserve_after, input_reserve_after)
+    };
+    pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+    emit!(LpChangeEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        change_type: LpChangeType::Deposit,
+        lp_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
+
+    Ok(())
+}
@@ -1,14 +1,18 @@
 use anchor_lang::{prelude::*, system_program};
 use anchor_spl::{
     associated_token::AssociatedToken,
-    token::{self, Mint, MintTo, Token, TokenAccount, Transfer},
+    token::Token,
+    token_interface::{self, Mint, MintTo, TokenAccount, TokenInterface},
 };
 
 use crate::{
-    constants::{AUTH_SEED, LP_MINT_SEED, POOL_SEED, VAULT_SEED},
+    constants::{
+        AUTH_SEED, LOCKED_LP_AMOUNT, LP_MINT_SEED, MINT_ALLOWLIST_SEED, POOL_SEED, VAULT_SEED,
+    },
     error::ErrorCode,
     instructions::CONFIG_SEED,
-    states::{PoolState},
+    states::{CreatorFeeOn, MintAllowlist, PoolCreatedEvent, PoolState},
+    utils::{check_mint_extensions, get_transfer_fee, transfer_from_user_to_pool_vault},
     AmmConfig,
 };
 
@@ -33,48 +37,57 @@ pub struct CreatePool<'info> {
     )]
     pub pool_state: Box<Account<'info, PoolState>>,
 
-    #[account(constraint = token_0_mint.key() < token_1_mint.key())]
-    pub token_0_mint: Account<'info, Mint>,
-    pub token_1_mint: Account<'info, Mint>,
+    #[account(constraint = token_0_mint.key() < token_1_mint.key(), mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
     /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(seeds = [AUTH_SEED], bump)]
     pub authority: UncheckedAccount<'info>,
 
-    #[account(init, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump, payer = creator, mint::decimals = 9, mint::authority = authority)]
-    pub lp_mint: Account<'info, Mint>,
+    #[account(init, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump, payer = creator, mint::decimals = 9, mint::authority = authority, mint::token_program = token_program)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(init, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_0_mint.key().as_ref()], bump, payer = creator, token::mint = token_0_mint, token::authority = authority)]
-    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+    #[account(init, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_0_mint.key().as_ref()], bump, payer = creator, token::mint = token_0_mint, token::authority = authority, token::token_program = token_0_program)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(init, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_1_mint.key().as_ref()], bump, payer = creator, token::mint = token_1_mint, token::authority = authority)]
-    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+    #[account(init, seeds=[VAULT_SEED, pool_state.key().as_ref(), token_1_mint.key().as_ref()], bump, payer = creator, token::mint = token_1_mint, token::authority = authority, token::token_program = token_1_program)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     // Creator
-    #[account(mut, token::mint = token_0_mint, token::authority = creator)]
-    pub creator_token_0: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_0_mint, token::authority = creator, token::token_program = token_0_program)]
+    pub creator_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, token::mint = token_1_mint, token::authority = creator)]
-    pub creator_token_1: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = token_1_mint, token::authority = creator, token::token_program = token_1_program)]
+    pub creator_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(init, associated_token::mint = lp_mint, associated_token::authority = creator, payer = creator)]
-    pub creator_lp: Box<Account<'info, TokenAccount>>,
+    #[account(init, associated_token::mint = lp_mint, associated_token::authority = creator, associated_token::token_program = token_program, payer = creator)]
+    pub creator_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Only needed when a mint relies on an allowlist exception
+    #[account(seeds=[MINT_ALLOWLIST_SEED], bump = mint_allowlist.bump)]
+    pub mint_allowlist: Option<Box<Account<'info, MintAllowlist>>>,
 
     /// CHECK: Normal Sol Wallet Account
     #[account(mut, address = amm_config.fund_owner @ ErrorCode::InvalidFeeReceiver)]
     pub fee_receiver: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
+    // LP mint always lives under the legacy token program
     pub token_program: Program<'info, Token>,
+    pub token_0_program: Interface<'info, TokenInterface>,
+    pub token_1_program: Interface<'info, TokenInterface>,
     pub associated_token_program: Program<'info, AssociatedToken>,
 }
 
-pub fn create_pool(
-    ctx: Context<CreatePool>,
+pub fn create_pool<'info>(
+    ctx: Context<'_, '_, 'info, 'info, CreatePool<'info>>,
     _index: u16,
     init_amount_0: u64,
     init_amount_1: u64,
     mut open_time: u64,
+    creator_fee_on: CreatorFeeOn,
 ) -> Result<()> {
 
     require!(init_amount_0 > 0, ErrorCode::InvalidTokenAmount);
@@ -92,38 +105,52 @@ pub fn create_pool(
         ErrorCode::PoolCreationDisabled
     );
 
-    let token_program_token_0 = ctx.accounts.token_program.to_account_info();
-
-    let cpi_transfer_token_0_accounts = Transfer {
-        from: ctx.accounts.creator_token_0.to_account_info(),
-        to: ctx.accounts.token_0_vault.to_account_info(),
-        authority: ctx.accounts.creator.to_account_info(),
-    };
-
-    let cpi_context = CpiContext::new(token_program_token_0, cpi_transfer_token_0_accounts);
-    token::transfer(cpi_context, init_amount_0)?;
-
-    // Step 3
-    let cpi_transfer_token_1_accounts = Transfer {
-        from: ctx.accounts.creator_token_1.to_account_info(),
-        to: ctx.accounts.token_1_vault.to_account_info(),
-        authority: ctx.accounts.creator.to_account_info(),
-    };
-
-    let token_program_token_1 = ctx.accounts.token_program.to_account_info();
-    let cpi_context = CpiContext::new(token_program_token_1, cpi_transfer_token_1_accounts);
-    token::transfer(cpi_context, init_amount_1)?;
+    let mint_allowlist = ctx
+        .accounts
+        .mint_allowlist
+        .as_deref()
+        .map(|allowlist| &**allowlist);
+    check_mint_extensions(&ctx.accounts.token_0_mint, mint_allowlist)?;
+    check_mint_extensions(&ctx.accounts.token_1_mint, mint_allowlist)?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_token_0.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        init_amount_0,
+        ctx.remaining_accounts,
+    )?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.creator.to_account_info(),
+        ctx.accounts.creator_token_1.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        init_amount_1,
+        ctx.remaining_accounts,
+    )?;
+
+    // Vaults only receive what is left after any Token-2022 transfer fee
+    let vault_0_amount = init_amount_0
+        .checked_sub(get_transfer_fee(&ctx.accounts.token_0_mint, init_amount_0)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let vault_1_amount = init_amount_1
+        .checked_sub(get_transfer_fee(&ctx.accounts.token_1_mint, init_amount_1)?)
+        .ok_or(ErrorCode::MathOverflow)?;
 
     // Just calculate intial LP tokens to mint
-    let product = (init_amount_0 as u128) * (init_amount_1 as u128);
+    let product = (vault_0_amount as u128) * (vault_1_amount as u128);
     let initial_lp_liquidity = product.isqrt() as u64;
 
     require!(
-        initial_lp_liquidity > 100,
+        initial_lp_liquidity > LOCKED_LP_AMOUNT,
         ErrorCode::InsufficientTokensToMint
     );
 
-    let creator_lp_amount = initial_lp_liquidity - 100;
+    let creator_lp_amount = initial_lp_liquidity - LOCKED_LP_AMOUNT;
 
     let seeds = &[AUTH_SEED, &[ctx.bumps.authority]];
     let signer_seeds = &[&seeds[..]];
@@ -136,7 +163,7 @@ pub fn create_pool(
 
     let mint_program = ctx.accounts.token_program.to_account_info();
     let mint_context = CpiContext::new_with_signer(mint_program, mint_accounts, signer_seeds);
-    token::mint_to(mint_context, creator_lp_amount)?;
+    token_interface::mint_to(mint_context, creator_lp_amount)?;
 
     // Transfer pool creation fee.
     let amount = amm_config.create_pool_fee;
@@ -158,8 +185,8 @@ pub fn create_pool(
     pool_state.token_0_mint = ctx.accounts.token_0_mint.key();
     pool_state.token_1_mint = ctx.accounts.token_1_mint.key();
     pool_state.lp_mint = ctx.accounts.lp_mint.key();
-    pool_state.token_0_program = ctx.accounts.token_program.key();
-    pool_state.token_1_program = ctx.accounts.token_program.key();
+    pool_state.token_0_program = ctx.accounts.token_0_program.key();
+    pool_state.token_1_program = ctx.accounts.token_1_program.key();
 
     // Config
     pool_state.auth_bump = ctx.bumps.authority;
@@ -171,6 +198,7 @@ pub fn create_pool(
     pool_state.status = 0; // all operations enabled
     pool_state.open_time = open_time;
     pool_state.recent_epoch = Clock::get()?.epoch;
+    pool_state.init_oracle(vault_0_amount, vault_1_amount, block_timestamp);
 
     pool_state.lp_supply = initial_lp_liquidity;
 
@@ -185,7 +213,24 @@ pub fn create_pool(
     pool_state.creator_token_0_fee = 0;
     pool_state.creator_token_1_fee = 0;
 
-    pool_state.creator_fee_on = 0;
+    pool_state.creator_fee_on = creator_fee_on as u8;
     pool_state.creator_fee_active = true;
+
+    emit!(PoolCreatedEvent {
+        pool_id: pool_state.key(),
+        amm_config: pool_state.amm_config,
+        pool_creator: pool_state.pool_creator,
+        token_0_mint: pool_state.token_0_mint,
+        token_1_mint: pool_state.token_1_mint,
+        token_0_vault: pool_state.token_0_vault,
+        token_1_vault: pool_state.token_1_vault,
+        lp_mint: pool_state.lp_mint,
+        reserve_0: vault_0_amount,
+        reserve_1: vault_1_amount,
+        lp_supply: initial_lp_liquidity,
+        open_time,
+        creator_fee_on,
+    });
+
     Ok(())
 }
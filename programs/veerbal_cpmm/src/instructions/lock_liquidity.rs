@@ -0,0 +1,98 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{Mint, TokenAccount},
+};
+
+use crate::{
+    constants::{AUTH_SEED, LOCKED_LP_VAULT_SEED, LOCKED_POSITION_SEED, LP_MINT_SEED, POOL_SEED},
+    curve::{lp_to_liquidity, pool_liquidity},
+    error::ErrorCode,
+    states::{LiquidityLockedEvent, LockedPosition, PoolState},
+    utils::transfer_from_user_to_pool_vault,
+};
+
+#[derive(Accounts)]
+pub struct LockLiquidity<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(address = pool_state.token_0_vault @ ErrorCode::InvalidVault)]
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(address = pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = signer, token::token_program = token_program)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Shared by every position in the pool, nothing ever transfers out of it
+    #[account(init_if_needed, payer = signer, seeds=[LOCKED_LP_VAULT_SEED, pool_state.key().as_ref()], bump, token::mint = lp_mint, token::authority = authority, token::token_program = token_program)]
+    pub locked_lp_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = signer, seeds=[LOCKED_POSITION_SEED, pool_state.key().as_ref(), signer.key().as_ref()], bump, space = LockedPosition::LEN)]
+    pub locked_position: Box<Account<'info, LockedPosition>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Lock LP for good. The position records the sqrt(k) share it holds now,
+/// so later growth from fees can be claimed without touching the principal
+pub fn lock_liquidity(ctx: Context<LockLiquidity>, lp_amount: u64) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidLPAmount);
+    let pool_state = &ctx.accounts.pool_state;
+
+    let (clean_vault_0, clean_vault_1) = pool_state.vault_amount_without_fee(
+        ctx.accounts.token_0_vault.amount,
+        ctx.accounts.token_1_vault.amount,
+    )?;
+    let liquidity = lp_to_liquidity(
+        lp_amount,
+        pool_liquidity(clean_vault_0, clean_vault_1),
+        pool_state.lp_supply,
+    )
+    .ok_or(ErrorCode::MathOverflow)?;
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer_lp.to_account_info(),
+        ctx.accounts.locked_lp_vault.to_account_info(),
+        &ctx.accounts.lp_mint,
+        ctx.accounts.token_program.to_account_info(),
+        lp_amount,
+        &[],
+    )?;
+
+    let locked_position = &mut ctx.accounts.locked_position;
+    locked_position.bump = ctx.bumps.locked_position;
+    locked_position.pool_id = pool_state.key();
+    locked_position.owner = ctx.accounts.signer.key();
+    locked_position.lp_amount = locked_position
+        .lp_amount
+        .checked_add(lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    locked_position.locked_liquidity = locked_position
+        .locked_liquidity
+        .checked_add(liquidity)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    emit!(LiquidityLockedEvent {
+        pool_id: pool_state.key(),
+        owner: ctx.accounts.signer.key(),
+        lp_amount,
+        position_lp_amount: locked_position.lp_amount,
+    });
+
+    Ok(())
+}
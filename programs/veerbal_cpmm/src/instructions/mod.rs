@@ -1,21 +1,36 @@
 pub mod initialize;
 pub use initialize::*;
 
+pub mod admin;
+pub use admin::*;
+
 pub mod create_config;
 pub use create_config::*;
 
+pub mod update_config;
+pub use update_config::*;
+
 pub mod deposit;
 pub use deposit::*;
 
+pub mod deposit_single_token;
+pub use deposit_single_token::*;
+
 pub mod withdraw;
 pub use withdraw::*;
 
+pub mod withdraw_single_token;
+pub use withdraw_single_token::*;
+
 pub mod swap_base_input;
 pub use swap_base_input::*;
 
 pub mod swap_base_output;
 pub use swap_base_output::*;
 
+pub mod swap_route;
+pub use swap_route::*;
+
 pub mod collect_creator_fee;
 pub use collect_creator_fee::*;
 
@@ -24,3 +39,36 @@ pub use collect_protocol_fee::*;
 
 pub mod collect_fund_fee;
 pub use collect_fund_fee::*;
+
+pub mod close_pool;
+pub use close_pool::*;
+
+pub mod lock_liquidity;
+pub use lock_liquidity::*;
+
+pub mod collect_locked_fee;
+pub use collect_locked_fee::*;
+
+pub mod vesting;
+pub use vesting::*;
+
+pub mod update_pool_status;
+pub use update_pool_status::*;
+
+pub mod update_global_status;
+pub use update_global_status::*;
+
+pub mod update_config_status;
+pub use update_config_status::*;
+
+pub mod update_pool_fees;
+pub use update_pool_fees::*;
+
+pub mod update_pool_creator;
+pub use update_pool_creator::*;
+
+pub mod update_mint_allowlist;
+pub use update_mint_allowlist::*;
+
+pub mod observe;
+pub use observe::*;
@@ -0,0 +1,46 @@
+use crate::{
+    constants::POOL_SEED,
+    error::ErrorCode,
+    states::{PoolState, TwapPrice},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct Observe<'info> {
+    #[account(seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+}
+
+/// Read-only TWAP over at least the last `seconds_ago` seconds, extended to now
+/// with the reserves stored by the pool's last instruction. The ring buffer
+/// holds about 8 hours at best, longer windows fail with `OracleNotReady`
+pub fn observe(ctx: Context<Observe>, seconds_ago: u64) -> Result<TwapPrice> {
+    require!(seconds_ago > 0, ErrorCode::InvalidObservationWindow);
+
+    let pool_state = &ctx.accounts.pool_state;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let target_timestamp = block_timestamp
+        .checked_sub(seconds_ago)
+        .ok_or(ErrorCode::InvalidObservationWindow)?;
+
+    // Newest observation at or before the start of the window
+    let start = pool_state
+        .observations
+        .iter()
+        .filter(|observation| {
+            observation.block_timestamp != 0 && observation.block_timestamp <= target_timestamp
+        })
+        .max_by_key(|observation| observation.block_timestamp)
+        .ok_or(ErrorCode::OracleNotReady)?;
+
+    let (cumulative_0, cumulative_1) = pool_state.cumulative_prices_at(block_timestamp);
+
+    let window = block_timestamp - start.block_timestamp;
+    Ok(TwapPrice {
+        token_0_price_x32: cumulative_0.wrapping_sub(start.cumulative_token_0_price_x32)
+            / window as u128,
+        token_1_price_x32: cumulative_1.wrapping_sub(start.cumulative_token_1_price_x32)
+            / window as u128,
+        window,
+    })
+}
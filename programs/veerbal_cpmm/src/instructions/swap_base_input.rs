@@ -1,11 +1,18 @@
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
-    constants::{AUTH_SEED, POOL_SEED},
+    constants::{ADMIN_SEED, AUTH_SEED, POOL_SEED},
     curve::{creator_fee, fund_fee, protocol_fee, swap_base_input_without_fees, trade_fee},
     error::ErrorCode,
-    states::{PoolState, PoolStatusBitIndex},
+    states::{AdminState, PoolState, PoolStatusBitIndex, SwapEvent},
+    utils::{
+        get_transfer_fee, is_native_mint, transfer_from_pool_vault_to_user,
+        transfer_from_user_to_pool_vault, unwrap_native_sol, wrap_native_sol,
+    },
     AmmConfig,
 };
 
@@ -20,25 +27,28 @@ pub struct Swap<'info> {
     #[account(address = pool_state.amm_config)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
-    #[account(address =  input_vault.mint @ ErrorCode::MintMismatch)]
-    pub input_token_mint: Account<'info, Mint>,
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(address =  input_vault.mint @ ErrorCode::MintMismatch, mint::token_program = input_token_program)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(address = output_vault.mint @ ErrorCode::MintMismatch)]
-    pub output_token_mint: Account<'info, Mint>,
+    #[account(address = output_vault.mint @ ErrorCode::MintMismatch, mint::token_program = output_token_program)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
 
     #[account(mut, constraint = input_vault.key() == pool_state.token_0_vault || input_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault,)]
-    pub input_vault: Box<Account<'info, TokenAccount>>,
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut,  constraint = output_vault.key() == pool_state.token_0_vault 
     || output_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, constraint = output_vault.key() != input_vault.key() @ ErrorCode::SameVault)]
-    pub output_vault: Box<Account<'info, TokenAccount>>,
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    // User accounts
-    #[account(mut, token::mint = input_token_mint, token::authority = signer)]
-    pub input_token_account: Box<Account<'info, TokenAccount>>,
+    // User accounts, created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = input_token_mint, associated_token::authority = signer, associated_token::token_program = input_token_program)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, token::mint = output_token_mint, token::authority = signer)]
-    pub output_token_account: Box<Account<'info, TokenAccount>>,
+    #[account(init_if_needed, payer = signer, associated_token::mint = output_token_mint, associated_token::authority = signer, associated_token::token_program = output_token_program)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
 
     // Authority
     /// CHECK: This is not dangerous because we don't read or write from this account
@@ -46,17 +56,30 @@ pub struct Swap<'info> {
     pub authority: UncheckedAccount<'info>,
 
     // Programs
-    pub token_program: Program<'info, Token>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
+pub fn swap<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    native_sol: bool,
+) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
     require!(
-        pool_state.is_enabled(PoolStatusBitIndex::Swap),
+        pool_state.is_enabled(PoolStatusBitIndex::Swap)
+            && amm_config.is_enabled(PoolStatusBitIndex::Swap)
+            && admin_state.is_enabled(PoolStatusBitIndex::Swap),
         ErrorCode::SwapBlocked
     );
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
     require!(
-        pool_state.open_time < Clock::get()?.unix_timestamp as u64,
+        pool_state.open_time < block_timestamp,
         ErrorCode::SwapBlocked
     );
     require!(amount_in > 0, ErrorCode::InvalidTokenAmount);
@@ -90,57 +113,61 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Resu
         .checked_mul(output_vault_balance)
         .ok_or(ErrorCode::MathOverflow)?;
 
-    // Calculate trade fee
-    let fee = trade_fee(amount_in as u128, ctx.accounts.amm_config.trade_fee_rate)
-        .ok_or(ErrorCode::MathOverflow)?;
-    let actual_input: u128 = (amount_in as u128)
-        .checked_sub(fee)
+    // The vault only receives what is left after the input transfer fee
+    let transfer_fee_in = get_transfer_fee(&ctx.accounts.input_token_mint, amount_in)?;
+    let vault_amount_in = amount_in
+        .checked_sub(transfer_fee_in)
         .ok_or(ErrorCode::MathOverflow)?;
-    // Split fee into protocol/fund/creator
-    let protocol_fee_amount = protocol_fee(fee, ctx.accounts.amm_config.protocol_fee_rate)
+    require!(vault_amount_in > 0, ErrorCode::InvalidTokenAmount);
+
+    // Calculate trade fee
+    let trade_fee_rate = pool_state.trade_fee_rate(&ctx.accounts.amm_config, block_timestamp)?;
+    let fee = trade_fee(vault_amount_in as u128, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    // Split fee into protocol/fund
+    let protocol_fee_amount = protocol_fee(fee, pool_state.protocol_fee_rate(amm_config))
         .ok_or(ErrorCode::MathOverflow)?;
     let fund_fee_amount =
-        fund_fee(fee, ctx.accounts.amm_config.fund_fee_rate).ok_or(ErrorCode::MathOverflow)?;
-    let creator_fee_amount = creator_fee(fee, ctx.accounts.amm_config.creator_fee_rate)
+        fund_fee(fee, pool_state.fund_fee_rate(amm_config)).ok_or(ErrorCode::MathOverflow)?;
+    // The creator fee is a separate surcharge on the input or the output
+    let is_creator_fee_on_input = pool_state.is_creator_fee_on_input(is_token_0_input);
+    let (input_creator_fee_rate, output_creator_fee_rate) =
+        pool_state.creator_fee_rates(amm_config, is_token_0_input);
+    let creator_fee_in = creator_fee(vault_amount_in as u128, input_creator_fee_rate)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let actual_input: u128 = (vault_amount_in as u128)
+        .checked_sub(fee)
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
         .ok_or(ErrorCode::MathOverflow)?;
-    // Track fees in pool_state
-    if is_token_0_input {
-        pool_state.protocol_token_0_fee = pool_state
-            .protocol_token_0_fee
-            .checked_add(protocol_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.fund_token_0_fee = pool_state
-            .fund_token_0_fee
-            .checked_add(fund_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.creator_token_0_fee = pool_state
-            .creator_token_0_fee
-            .checked_add(creator_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-    } else {
-        pool_state.protocol_token_1_fee = pool_state
-            .protocol_token_1_fee
-            .checked_add(protocol_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.fund_token_1_fee = pool_state
-            .fund_token_1_fee
-            .checked_add(fund_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.creator_token_1_fee = pool_state
-            .creator_token_1_fee
-            .checked_add(creator_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-    }
     // Calculate output using x*y=k formula
     let output_amount =
         swap_base_input_without_fees(actual_input, input_vault_balance, output_vault_balance)
             .ok_or(ErrorCode::MathOverflow)?;
     require!(output_amount > 0, ErrorCode::ZeroTradingTokens);
-    let output_amount: u64 = output_amount
+    let creator_fee_out =
+        creator_fee(output_amount, output_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let vault_amount_out: u64 = output_amount
+        .checked_sub(creator_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?
         .try_into()
         .map_err(|_| ErrorCode::MathOverflow)?;
+    require!(vault_amount_out > 0, ErrorCode::ZeroTradingTokens);
+    let (creator_fee_in, creator_fee_out) =
+        pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
+    // Track fees in pool_state
+    pool_state.accrue_fees(
+        is_token_0_input,
+        protocol_fee_amount as u64,
+        fund_fee_amount as u64,
+        creator_fee_in as u64,
+    )?;
+    pool_state.accrue_fees(!is_token_0_input, 0, 0, creator_fee_out as u64)?;
+    // Slippage applies to what the user receives after the output transfer fee
+    let transfer_fee_out = get_transfer_fee(&ctx.accounts.output_token_mint, vault_amount_out)?;
+    let amount_received = vault_amount_out
+        .checked_sub(transfer_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?;
     require!(
-        output_amount >= minimum_amount_out,
+        amount_received >= minimum_amount_out,
         ErrorCode::SlippageExceeded
     );
 
@@ -148,7 +175,7 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Resu
         .checked_add(actual_input)
         .ok_or(ErrorCode::MathOverflow)?;
     let new_output_balance = output_vault_balance
-        .checked_sub(output_amount as u128)
+        .checked_sub(output_amount)
         .ok_or(ErrorCode::MathOverflow)?;
     let constant_after = new_input_balance
         .checked_mul(new_output_balance)
@@ -159,32 +186,106 @@ pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Resu
         ErrorCode::ConstantProductInvariant
     );
 
+    let is_native_input = native_sol && is_native_mint(&ctx.accounts.input_token_mint.key());
+    let is_native_output = native_sol && is_native_mint(&ctx.accounts.output_token_mint.key());
+    if is_native_input {
+        wrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            amount_in,
+        )?;
+    }
     // Transfer IN: user → input_vault
-    let transfer_in_accounts = Transfer {
-        from: ctx.accounts.input_token_account.to_account_info(),
-        to: ctx.accounts.input_vault.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
-    };
-    let cpi_ctx = CpiContext::new(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_in_accounts,
-    );
-    token::transfer(cpi_ctx, amount_in)?;
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        &ctx.accounts.input_token_mint,
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in,
+        ctx.remaining_accounts,
+    )?;
     // Transfer OUT: output_vault → user (PDA signs!)
-    let transfer_out_accounts = Transfer {
-        from: ctx.accounts.output_vault.to_account_info(),
-        to: ctx.accounts.output_token_account.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
     let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
     let signer_seeds = &[&seeds[..]];
-    let cpi_ctx = CpiContext::new_with_signer(
-        ctx.accounts.token_program.to_account_info(),
-        transfer_out_accounts,
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        &ctx.accounts.output_token_mint,
+        ctx.accounts.output_token_program.to_account_info(),
+        vault_amount_out,
         signer_seeds,
-    );
-    token::transfer(cpi_ctx, output_amount)?;
+        ctx.remaining_accounts,
+    )?;
+    if is_native_input {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            ctx.accounts.input_token_account.amount,
+        )?;
+    }
+    if is_native_output {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.output_token_account.to_account_info(),
+            ctx.accounts.output_token_program.to_account_info(),
+            ctx.accounts.output_token_account.amount,
+        )?;
+    }
 
     pool_state.recent_epoch = Clock::get()?.epoch;
+
+    // The LP share of the trade fee stays in the input reserve
+    let input_reserve_after: u64 = input_vault_balance
+        .checked_add(vault_amount_in as u128)
+        .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+        .and_then(|amount| amount.checked_sub(fund_fee_amount))
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let output_reserve_after: u64 = output_vault_balance
+        .checked_sub(vault_amount_out as u128)
+        .and_then(|amount| amount.checked_sub(creator_fee_out))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let (reserve_0_after, reserve_1_after) = if is_token_0_input {
+        (input_reserve_after, output_reserve_after)
+    } else {
+        (output_reserve_after, input_reserve_after)
+    };
+    pool_state.update_volatility(
+        &ctx.accounts.amm_config,
+        (clean_vault_0, clean_vault_1),
+        (reserve_0_after, reserve_1_after),
+        block_timestamp,
+    );
+    pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+    emit!(SwapEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        is_token_0_input,
+        base_input: true,
+        amount_in,
+        amount_out: amount_received,
+        input_transfer_fee: transfer_fee_in,
+        output_transfer_fee: transfer_fee_out,
+        trade_fee_rate,
+        trade_fee: fee as u64,
+        protocol_fee: protocol_fee_amount as u64,
+        fund_fee: fund_fee_amount as u64,
+        is_creator_fee_on_input,
+        creator_fee: (creator_fee_in + creator_fee_out) as u64,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
     Ok(())
 }
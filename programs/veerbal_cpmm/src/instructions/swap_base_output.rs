@@ -1,12 +1,22 @@
 use crate::{
-    constants::{AUTH_SEED, POOL_SEED},
-    curve::{creator_fee, fund_fee, protocol_fee, swap_base_output_without_fees, trade_fee},
+    constants::{ADMIN_SEED, AUTH_SEED, POOL_SEED},
+    curve::{
+        amount_with_fees, creator_fee, fund_fee, protocol_fee, swap_base_output_without_fees,
+        trade_fee,
+    },
     error::ErrorCode,
-    states::{PoolState, PoolStatusBitIndex},
+    states::{AdminState, PoolState, PoolStatusBitIndex, SwapEvent},
+    utils::{
+        get_transfer_inverse_fee, is_native_mint, transfer_from_pool_vault_to_user,
+        transfer_from_user_to_pool_vault, unwrap_native_sol, wrap_native_sol,
+    },
     AmmConfig,
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
 #[derive(Accounts)]
 pub struct SwapBaseOutput<'info> {
     #[account(mut)]
@@ -15,35 +25,48 @@ pub struct SwapBaseOutput<'info> {
     pub pool_state: Box<Account<'info, PoolState>>,
     #[account(address = pool_state.amm_config)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
-    #[account(address = input_vault.mint @ ErrorCode::MintMismatch)]
-    pub input_token_mint: Account<'info, Mint>,
-    #[account(address = output_vault.mint @ ErrorCode::MintMismatch)]
-    pub output_token_mint: Account<'info, Mint>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+    #[account(address = input_vault.mint @ ErrorCode::MintMismatch, mint::token_program = input_token_program)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+    #[account(address = output_vault.mint @ ErrorCode::MintMismatch, mint::token_program = output_token_program)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
     #[account(mut, constraint = input_vault.key() == pool_state.token_0_vault || input_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault)]
-    pub input_vault: Box<Account<'info, TokenAccount>>,
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
     #[account(mut, constraint = output_vault.key() == pool_state.token_0_vault || output_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, constraint = output_vault.key() != input_vault.key() @ ErrorCode::SameVault)]
-    pub output_vault: Box<Account<'info, TokenAccount>>,
-    #[account(mut, token::mint = input_token_mint, token::authority = signer)]
-    pub input_token_account: Box<Account<'info, TokenAccount>>,
-    #[account(mut, token::mint = output_token_mint, token::authority = signer)]
-    pub output_token_account: Box<Account<'info, TokenAccount>>,
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+    // Created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = input_token_mint, associated_token::authority = signer, associated_token::token_program = input_token_program)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+    #[account(init_if_needed, payer = signer, associated_token::mint = output_token_mint, associated_token::authority = signer, associated_token::token_program = output_token_program)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
     /// CHECK: Authority PDA
     #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
     pub authority: UncheckedAccount<'info>,
-    pub token_program: Program<'info, Token>,
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
-pub fn swap_base_output(
-    ctx: Context<SwapBaseOutput>,
+pub fn swap_base_output<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapBaseOutput<'info>>,
     amount_out: u64,        // Exact output user wants
     maximum_amount_in: u64, // Max user is willing to pay (slippage)
+    native_sol: bool,       // Wrap/unwrap SOL for a native mint side
 ) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
     require!(
-        pool_state.is_enabled(PoolStatusBitIndex::Swap),
+        pool_state.is_enabled(PoolStatusBitIndex::Swap)
+            && amm_config.is_enabled(PoolStatusBitIndex::Swap)
+            && admin_state.is_enabled(PoolStatusBitIndex::Swap),
         ErrorCode::SwapBlocked
     );
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
     require!(
-        pool_state.open_time < Clock::get()?.unix_timestamp as u64,
+        pool_state.open_time < block_timestamp,
         ErrorCode::SwapBlocked
     );
     require!(amount_out > 0, ErrorCode::InvalidTokenAmount);
@@ -75,77 +98,69 @@ pub fn swap_base_output(
     let constant_before = input_vault_balance
         .checked_mul(output_vault_balance)
         .ok_or(ErrorCode::MathOverflow)?;
+    // The vault must send extra so the user receives exactly amount_out
+    let transfer_fee_out =
+        get_transfer_inverse_fee(&ctx.accounts.output_token_mint, amount_out)?;
+    let vault_amount_out = amount_out
+        .checked_add(transfer_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let trade_fee_rate = pool_state.trade_fee_rate(amm_config, block_timestamp)?;
+    // The creator fee is a separate surcharge on the input or the output
+    let is_creator_fee_on_input = pool_state.is_creator_fee_on_input(is_token_0_input);
+    let (input_creator_fee_rate, output_creator_fee_rate) =
+        pool_state.creator_fee_rates(amm_config, is_token_0_input);
+    // An output-side creator fee leaves the reserve on top of what is sent
+    let output_amount = amount_with_fees(vault_amount_out as u128, 0, output_creator_fee_rate)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let creator_fee_out = output_amount
+        .checked_sub(vault_amount_out as u128)
+        .ok_or(ErrorCode::MathOverflow)?;
     // Calculate required input for desired output (BEFORE fees)
-    let input_without_fee = swap_base_output_without_fees(
-        amount_out as u128,
-        input_vault_balance,
-        output_vault_balance,
-    )
-    .ok_or(ErrorCode::MathOverflow)?;
-    // Add trade fee to get total input needed
-    // input_with_fee = input_without_fee / (1 - fee_rate)
-    // Simplified: input_with_fee = input_without_fee * denominator / (denominator - fee_rate)
-    let fee_denominator = 1_000_000u128;
-    let fee_rate = ctx.accounts.amm_config.trade_fee_rate as u128;
-    let amount_in = input_without_fee
-        .checked_mul(fee_denominator)
-        .ok_or(ErrorCode::MathOverflow)?
-        .checked_div(
-            fee_denominator
-                .checked_sub(fee_rate)
-                .ok_or(ErrorCode::MathOverflow)?,
-        )
+    let input_without_fee =
+        swap_base_output_without_fees(output_amount, input_vault_balance, output_vault_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+    // Add trade and creator fees to get total input needed, rounding in favour of the pool
+    let amount_in = amount_with_fees(input_without_fee, trade_fee_rate, input_creator_fee_rate)
         .ok_or(ErrorCode::MathOverflow)?;
-    // Calculate fee from amount_in
-    let fee = trade_fee(amount_in, ctx.accounts.amm_config.trade_fee_rate)
+    // Calculate fees from amount_in
+    let fee = trade_fee(amount_in, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let creator_fee_in =
+        creator_fee(amount_in, input_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    // Split fees
+    let protocol_fee_amount = protocol_fee(fee, pool_state.protocol_fee_rate(amm_config))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let fund_fee_amount =
+        fund_fee(fee, pool_state.fund_fee_rate(amm_config)).ok_or(ErrorCode::MathOverflow)?;
+    let amount_in: u64 = amount_in
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    // The user also covers the input transfer fee so the vault receives amount_in
+    let transfer_fee_in = get_transfer_inverse_fee(&ctx.accounts.input_token_mint, amount_in)?;
+    let amount_in_with_fee = amount_in
+        .checked_add(transfer_fee_in)
         .ok_or(ErrorCode::MathOverflow)?;
     // Slippage check: input must not exceed maximum
     require!(
-        amount_in as u64 <= maximum_amount_in,
+        amount_in_with_fee <= maximum_amount_in,
         ErrorCode::SlippageExceeded
     );
-    // Split fees
-    let protocol_fee_amount = protocol_fee(fee, ctx.accounts.amm_config.protocol_fee_rate)
-        .ok_or(ErrorCode::MathOverflow)?;
-    let fund_fee_amount =
-        fund_fee(fee, ctx.accounts.amm_config.fund_fee_rate).ok_or(ErrorCode::MathOverflow)?;
-    let creator_fee_amount = creator_fee(fee, ctx.accounts.amm_config.creator_fee_rate)
-        .ok_or(ErrorCode::MathOverflow)?;
+    let (creator_fee_in, creator_fee_out) =
+        pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
     // Track fees
-    if is_token_0_input {
-        pool_state.protocol_token_0_fee = pool_state
-            .protocol_token_0_fee
-            .checked_add(protocol_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.fund_token_0_fee = pool_state
-            .fund_token_0_fee
-            .checked_add(fund_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.creator_token_0_fee = pool_state
-            .creator_token_0_fee
-            .checked_add(creator_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-    } else {
-        pool_state.protocol_token_1_fee = pool_state
-            .protocol_token_1_fee
-            .checked_add(protocol_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.fund_token_1_fee = pool_state
-            .fund_token_1_fee
-            .checked_add(fund_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-        pool_state.creator_token_1_fee = pool_state
-            .creator_token_1_fee
-            .checked_add(creator_fee_amount as u64)
-            .ok_or(ErrorCode::MathOverflow)?;
-    }
+    pool_state.accrue_fees(
+        is_token_0_input,
+        protocol_fee_amount as u64,
+        fund_fee_amount as u64,
+        creator_fee_in as u64,
+    )?;
+    pool_state.accrue_fees(!is_token_0_input, 0, 0, creator_fee_out as u64)?;
     // k verification: constant AFTER
     // Use input_without_fee (the pure swap amount that affects liquidity)
     let new_input_balance = input_vault_balance
         .checked_add(input_without_fee)
         .ok_or(ErrorCode::MathOverflow)?;
     let new_output_balance = output_vault_balance
-        .checked_sub(amount_out as u128)
+        .checked_sub(output_amount)
         .ok_or(ErrorCode::MathOverflow)?;
     let constant_after = new_input_balance
         .checked_mul(new_output_balance)
@@ -154,33 +169,106 @@ pub fn swap_base_output(
         constant_after >= constant_before,
         ErrorCode::ConstantProductInvariant
     );
+    let is_native_input = native_sol && is_native_mint(&ctx.accounts.input_token_mint.key());
+    let is_native_output = native_sol && is_native_mint(&ctx.accounts.output_token_mint.key());
+    if is_native_input {
+        wrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.system_program.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            amount_in_with_fee,
+        )?;
+    }
     // Transfer IN
-    let transfer_in = Transfer {
-        from: ctx.accounts.input_token_account.to_account_info(),
-        to: ctx.accounts.input_vault.to_account_info(),
-        authority: ctx.accounts.signer.to_account_info(),
-    };
-    token::transfer(
-        CpiContext::new(ctx.accounts.token_program.to_account_info(), transfer_in),
-        amount_in as u64,
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.input_token_account.to_account_info(),
+        ctx.accounts.input_vault.to_account_info(),
+        &ctx.accounts.input_token_mint,
+        ctx.accounts.input_token_program.to_account_info(),
+        amount_in_with_fee,
+        ctx.remaining_accounts,
     )?;
     // Transfer OUT
     let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
     let signer_seeds = &[&seeds[..]];
-    let transfer_out = Transfer {
-        from: ctx.accounts.output_vault.to_account_info(),
-        to: ctx.accounts.output_token_account.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
-    token::transfer(
-        CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            transfer_out,
-            signer_seeds,
-        ),
-        amount_out,
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        &ctx.accounts.output_token_mint,
+        ctx.accounts.output_token_program.to_account_info(),
+        vault_amount_out,
+        signer_seeds,
+        ctx.remaining_accounts,
     )?;
+    if is_native_input {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.input_token_account.to_account_info(),
+            ctx.accounts.input_token_program.to_account_info(),
+            ctx.accounts.input_token_account.amount,
+        )?;
+    }
+    if is_native_output {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.output_token_account.to_account_info(),
+            ctx.accounts.output_token_program.to_account_info(),
+            ctx.accounts.output_token_account.amount,
+        )?;
+    }
 
     pool_state.recent_epoch = Clock::get()?.epoch;
+
+    // The LP share of the trade fee stays in the input reserve
+    let input_reserve_after: u64 = input_vault_balance
+        .checked_add(amount_in as u128)
+        .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+        .and_then(|amount| amount.checked_sub(fund_fee_amount))
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let output_reserve_after: u64 = output_vault_balance
+        .checked_sub(vault_amount_out as u128)
+        .and_then(|amount| amount.checked_sub(creator_fee_out))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let (reserve_0_after, reserve_1_after) = if is_token_0_input {
+        (input_reserve_after, output_reserve_after)
+    } else {
+        (output_reserve_after, input_reserve_after)
+    };
+    pool_state.update_volatility(
+        &ctx.accounts.amm_config,
+        (clean_vault_0, clean_vault_1),
+        (reserve_0_after, reserve_1_after),
+        block_timestamp,
+    );
+    pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+    emit!(SwapEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        is_token_0_input,
+        base_input: false,
+        amount_in: amount_in_with_fee,
+        amount_out,
+        input_transfer_fee: transfer_fee_in,
+        output_transfer_fee: transfer_fee_out,
+        trade_fee_rate,
+        trade_fee: fee as u64,
+        protocol_fee: protocol_fee_amount as u64,
+        fund_fee: fund_fee_amount as u64,
+        is_creator_fee_on_input,
+        creator_fee: (creator_fee_in + creator_fee_out) as u64,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
     Ok(())
 }
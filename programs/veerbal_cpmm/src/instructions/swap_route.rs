@@ -0,0 +1,626 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ADMIN_SEED, AUTH_SEED},
+    curve::{
+        amount_with_fees, creator_fee, fund_fee, protocol_fee, swap_base_input_without_fees,
+        swap_base_output_without_fees, trade_fee,
+    },
+    error::ErrorCode,
+    states::{AdminState, PoolState, PoolStatusBitIndex, SwapEvent},
+    utils::{
+        get_transfer_fee, get_transfer_inverse_fee, is_native_mint,
+        transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault, unwrap_native_sol,
+        wrap_native_sol,
+    },
+    AmmConfig,
+};
+
+/// Remaining accounts per hop:
+/// [amm_config, pool_state, input_vault, output_vault, output_mint, output_token_program]
+pub const ROUTE_HOP_ACCOUNTS: usize = 6;
+pub const MAX_ROUTE_HOPS: usize = 4;
+
+#[derive(Accounts)]
+pub struct SwapRoute<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mint::token_program = input_token_program)]
+    pub input_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mint::token_program = output_token_program)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = input_token_mint, associated_token::authority = signer, associated_token::token_program = input_token_program)]
+    pub input_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = signer, associated_token::mint = output_token_mint, associated_token::authority = signer, associated_token::token_program = output_token_program)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    pub input_token_program: Interface<'info, TokenInterface>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+struct RouteHop<'info> {
+    amm_config: Account<'info, AmmConfig>,
+    pool_state: Account<'info, PoolState>,
+    input_vault: InterfaceAccount<'info, TokenAccount>,
+    output_vault: InterfaceAccount<'info, TokenAccount>,
+    output_mint: InterfaceAccount<'info, Mint>,
+    output_token_program: Interface<'info, TokenInterface>,
+}
+
+impl<'info> RouteHop<'info> {
+    fn load(accounts: &'info [AccountInfo<'info>]) -> Result<Self> {
+        let [amm_config, pool_state, input_vault, output_vault, output_mint, output_token_program] =
+            accounts
+        else {
+            return err!(ErrorCode::InvalidRoute);
+        };
+        require!(
+            pool_state.is_writable && input_vault.is_writable && output_vault.is_writable,
+            ErrorCode::InvalidRoute
+        );
+
+        let hop = Self {
+            amm_config: Account::try_from(amm_config)?,
+            pool_state: Account::try_from(pool_state)?,
+            input_vault: InterfaceAccount::try_from(input_vault)?,
+            output_vault: InterfaceAccount::try_from(output_vault)?,
+            output_mint: InterfaceAccount::try_from(output_mint)?,
+            output_token_program: Interface::try_from(output_token_program)?,
+        };
+
+        let pool_state = &hop.pool_state;
+        require_keys_eq!(
+            hop.amm_config.key(),
+            pool_state.amm_config,
+            ErrorCode::InvalidRoute
+        );
+        let (expected_output_vault, expected_output_program) =
+            if hop.input_vault.key() == pool_state.token_0_vault {
+                (pool_state.token_1_vault, pool_state.token_1_program)
+            } else if hop.input_vault.key() == pool_state.token_1_vault {
+                (pool_state.token_0_vault, pool_state.token_0_program)
+            } else {
+                return err!(ErrorCode::InvalidVault);
+            };
+        require_keys_eq!(
+            hop.output_vault.key(),
+            expected_output_vault,
+            ErrorCode::InvalidVault
+        );
+        require_keys_eq!(
+            hop.output_mint.key(),
+            hop.output_vault.mint,
+            ErrorCode::MintMismatch
+        );
+        require_keys_eq!(
+            hop.output_token_program.key(),
+            expected_output_program,
+            ErrorCode::InvalidRoute
+        );
+        Ok(hop)
+    }
+
+    fn is_token_0_input(&self) -> bool {
+        self.input_vault.key() == self.pool_state.token_0_vault
+    }
+
+    /// Check the pool accepts swaps and return the clean (vault_0, vault_1)
+    /// reserves from the pre-swap balances
+    fn open(&self, block_timestamp: u64) -> Result<(u64, u64)> {
+        let pool_state = &self.pool_state;
+        require!(
+            pool_state.is_enabled(PoolStatusBitIndex::Swap)
+                && self.amm_config.is_enabled(PoolStatusBitIndex::Swap),
+            ErrorCode::SwapBlocked
+        );
+        require!(
+            pool_state.open_time < block_timestamp,
+            ErrorCode::SwapBlocked
+        );
+        let (vault_0_amount, vault_1_amount) = if self.input_vault.key() == pool_state.token_0_vault
+        {
+            (self.input_vault.amount, self.output_vault.amount)
+        } else {
+            (self.output_vault.amount, self.input_vault.amount)
+        };
+        let (clean_vault_0, clean_vault_1) =
+            pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+        Ok((clean_vault_0, clean_vault_1))
+    }
+}
+
+/// Load every hop before any transfer, so vault balances are pre-swap, and
+/// check that each hop continues in the previous hop's output token. The route
+/// ends at the first hop that reaches the output mint, any accounts after it
+/// are the transfer hook accounts
+fn load_route<'info>(
+    accounts: &SwapRoute<'info>,
+    remaining_accounts: &'info [AccountInfo<'info>],
+) -> Result<(Vec<RouteHop<'info>>, &'info [AccountInfo<'info>])> {
+    let mut hops: Vec<RouteHop<'info>> = Vec::with_capacity(MAX_ROUTE_HOPS);
+    let mut current_mint = accounts.input_token_mint.key();
+    while current_mint != accounts.output_token_mint.key() || hops.is_empty() {
+        require!(hops.len() < MAX_ROUTE_HOPS, ErrorCode::InvalidRoute);
+        let start = hops.len() * ROUTE_HOP_ACCOUNTS;
+        let hop_accounts = remaining_accounts
+            .get(start..start + ROUTE_HOP_ACCOUNTS)
+            .ok_or(ErrorCode::InvalidRoute)?;
+        let hop = RouteHop::load(hop_accounts)?;
+        require_keys_eq!(hop.input_vault.mint, current_mint, ErrorCode::InvalidRoute);
+        require!(
+            hops.iter()
+                .all(|previous| previous.pool_state.key() != hop.pool_state.key()),
+            ErrorCode::InvalidRoute
+        );
+        current_mint = hop.output_mint.key();
+        hops.push(hop);
+    }
+    require_keys_eq!(
+        hops[hops.len() - 1].output_token_program.key(),
+        accounts.output_token_program.key(),
+        ErrorCode::InvalidRoute
+    );
+    let hook_accounts = &remaining_accounts[hops.len() * ROUTE_HOP_ACCOUNTS..];
+    Ok((hops, hook_accounts))
+}
+
+/// Pull `amount_in` from the user, send each hop's output to the next hop's
+/// input vault (the last one to the user) and persist the pools
+fn settle_route<'info>(
+    accounts: &SwapRoute<'info>,
+    authority_bump: u8,
+    hops: &[RouteHop<'info>],
+    amount_in: u64,
+    hop_outputs: &[u64],
+    native_sol: bool,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    let is_native_input = native_sol && is_native_mint(&accounts.input_token_mint.key());
+    let is_native_output = native_sol && is_native_mint(&accounts.output_token_mint.key());
+    if is_native_input {
+        wrap_native_sol(
+            accounts.signer.to_account_info(),
+            accounts.input_token_account.to_account_info(),
+            accounts.system_program.to_account_info(),
+            accounts.input_token_program.to_account_info(),
+            amount_in,
+        )?;
+    }
+    transfer_from_user_to_pool_vault(
+        accounts.signer.to_account_info(),
+        accounts.input_token_account.to_account_info(),
+        hops[0].input_vault.to_account_info(),
+        &accounts.input_token_mint,
+        accounts.input_token_program.to_account_info(),
+        amount_in,
+        hook_accounts,
+    )?;
+
+    // Every vault shares the authority PDA, so it can sign vault to vault moves
+    let seeds = &[AUTH_SEED, &[authority_bump]];
+    let signer_seeds = &[&seeds[..]];
+    for (index, hop) in hops.iter().enumerate() {
+        let to = match hops.get(index + 1) {
+            Some(next_hop) => next_hop.input_vault.to_account_info(),
+            None => accounts.output_token_account.to_account_info(),
+        };
+        transfer_from_pool_vault_to_user(
+            accounts.authority.to_account_info(),
+            hop.output_vault.to_account_info(),
+            to,
+            &hop.output_mint,
+            hop.output_token_program.to_account_info(),
+            hop_outputs[index],
+            signer_seeds,
+            hook_accounts,
+        )?;
+    }
+
+    if is_native_input {
+        unwrap_native_sol(
+            accounts.signer.to_account_info(),
+            accounts.input_token_account.to_account_info(),
+            accounts.input_token_program.to_account_info(),
+            accounts.input_token_account.amount,
+        )?;
+    }
+    if is_native_output {
+        unwrap_native_sol(
+            accounts.signer.to_account_info(),
+            accounts.output_token_account.to_account_info(),
+            accounts.output_token_program.to_account_info(),
+            accounts.output_token_account.amount,
+        )?;
+    }
+
+    // Pools loaded from remaining accounts are not persisted by Anchor
+    for hop in hops {
+        hop.pool_state.exit(&crate::ID)?;
+    }
+    Ok(())
+}
+
+/// Chain base input swaps through several pools. Intermediate amounts move
+/// vault to vault, so only the final output is checked against slippage.
+pub fn swap_route<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount_in: u64,
+    minimum_amount_out: u64,
+    native_sol: bool,
+) -> Result<()> {
+    require!(amount_in > 0, ErrorCode::InvalidTokenAmount);
+    require!(minimum_amount_out > 0, ErrorCode::InvalidTokenAmount);
+    // Each hop checks its own pool, the global pause covers them all
+    require!(
+        ctx.accounts
+            .admin_state
+            .is_enabled(PoolStatusBitIndex::Swap),
+        ErrorCode::SwapBlocked
+    );
+
+    let (mut hops, hook_accounts) = load_route(ctx.accounts, ctx.remaining_accounts)?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let epoch = Clock::get()?.epoch;
+
+    // Amount sent into the current hop's input vault, before transfer fees
+    let mut hop_amount_in = amount_in;
+    let mut hop_outputs = Vec::with_capacity(hops.len());
+    for index in 0..hops.len() {
+        let (previous_hops, remaining_hops) = hops.split_at_mut(index);
+        let input_mint = match previous_hops.last() {
+            Some(previous_hop) => &previous_hop.output_mint,
+            None => &ctx.accounts.input_token_mint,
+        };
+        let transfer_fee_in = get_transfer_fee(input_mint, hop_amount_in)?;
+        let hop = &mut remaining_hops[0];
+        let is_token_0_input = hop.is_token_0_input();
+        let (clean_vault_0, clean_vault_1) = hop.open(block_timestamp)?;
+        let (input_vault_balance, output_vault_balance) = if is_token_0_input {
+            (clean_vault_0 as u128, clean_vault_1 as u128)
+        } else {
+            (clean_vault_1 as u128, clean_vault_0 as u128)
+        };
+
+        let vault_amount_in = hop_amount_in
+            .checked_sub(transfer_fee_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(vault_amount_in > 0, ErrorCode::InvalidTokenAmount);
+
+        let amm_config = &hop.amm_config;
+        let trade_fee_rate = hop.pool_state.trade_fee_rate(amm_config, block_timestamp)?;
+        let fee =
+            trade_fee(vault_amount_in as u128, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+        let protocol_fee_amount = protocol_fee(fee, hop.pool_state.protocol_fee_rate(amm_config))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let fund_fee_amount = fund_fee(fee, hop.pool_state.fund_fee_rate(amm_config))
+            .ok_or(ErrorCode::MathOverflow)?;
+        // The creator fee is a separate surcharge on the input or the output
+        let is_creator_fee_on_input = hop.pool_state.is_creator_fee_on_input(is_token_0_input);
+        let (input_creator_fee_rate, output_creator_fee_rate) = hop
+            .pool_state
+            .creator_fee_rates(amm_config, is_token_0_input);
+        let creator_fee_in = creator_fee(vault_amount_in as u128, input_creator_fee_rate)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let actual_input = (vault_amount_in as u128)
+            .checked_sub(fee)
+            .and_then(|amount| amount.checked_sub(creator_fee_in))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        let output_amount =
+            swap_base_input_without_fees(actual_input, input_vault_balance, output_vault_balance)
+                .ok_or(ErrorCode::MathOverflow)?;
+        require!(output_amount > 0, ErrorCode::ZeroTradingTokens);
+
+        // k must not shrink with what actually stays after the fee
+        let constant_before = input_vault_balance
+            .checked_mul(output_vault_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let constant_after = input_vault_balance
+            .checked_add(actual_input)
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_mul(
+                output_vault_balance
+                    .checked_sub(output_amount)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            constant_after >= constant_before,
+            ErrorCode::ConstantProductInvariant
+        );
+
+        let creator_fee_out =
+            creator_fee(output_amount, output_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+        // What the vault sends on, the creator fee stays behind
+        let vault_amount_out: u64 = output_amount
+            .checked_sub(creator_fee_out)
+            .ok_or(ErrorCode::MathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        require!(vault_amount_out > 0, ErrorCode::ZeroTradingTokens);
+        let pool_state = &mut hop.pool_state;
+        let (creator_fee_in, creator_fee_out) =
+            pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
+        pool_state.accrue_fees(
+            is_token_0_input,
+            protocol_fee_amount as u64,
+            fund_fee_amount as u64,
+            creator_fee_in as u64,
+        )?;
+        pool_state.accrue_fees(!is_token_0_input, 0, 0, creator_fee_out as u64)?;
+        pool_state.recent_epoch = epoch;
+
+        // The LP share of the trade fee stays in the input reserve
+        let input_reserve_after: u64 = input_vault_balance
+            .checked_add(vault_amount_in as u128)
+            .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+            .and_then(|amount| amount.checked_sub(fund_fee_amount))
+            .and_then(|amount| amount.checked_sub(creator_fee_in))
+            .ok_or(ErrorCode::MathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        let output_reserve_after: u64 = output_vault_balance
+            .checked_sub(vault_amount_out as u128)
+            .and_then(|amount| amount.checked_sub(creator_fee_out))
+            .ok_or(ErrorCode::MathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        let (reserve_0_after, reserve_1_after) = if is_token_0_input {
+            (input_reserve_after, output_reserve_after)
+        } else {
+            (output_reserve_after, input_reserve_after)
+        };
+        pool_state.update_volatility(
+            &hop.amm_config,
+            (clean_vault_0, clean_vault_1),
+            (reserve_0_after, reserve_1_after),
+            block_timestamp,
+        );
+        pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+        let transfer_fee_out = get_transfer_fee(&hop.output_mint, vault_amount_out)?;
+        emit!(SwapEvent {
+            pool_id: pool_state.key(),
+            user: ctx.accounts.signer.key(),
+            is_token_0_input,
+            base_input: true,
+            amount_in: hop_amount_in,
+            amount_out: vault_amount_out - transfer_fee_out,
+            input_transfer_fee: transfer_fee_in,
+            output_transfer_fee: transfer_fee_out,
+            trade_fee_rate,
+            trade_fee: fee as u64,
+            protocol_fee: protocol_fee_amount as u64,
+            fund_fee: fund_fee_amount as u64,
+            is_creator_fee_on_input,
+            creator_fee: (creator_fee_in + creator_fee_out) as u64,
+            reserve_0_before: clean_vault_0,
+            reserve_1_before: clean_vault_1,
+            reserve_0_after,
+            reserve_1_after,
+            lp_supply: pool_state.lp_supply,
+        });
+
+        hop_outputs.push(vault_amount_out);
+        hop_amount_in = vault_amount_out;
+    }
+
+    // Slippage applies to what the user receives after the output transfer fee
+    let last_hop = &hops[hops.len() - 1];
+    let amount_received = hop_amount_in
+        .checked_sub(get_transfer_fee(&last_hop.output_mint, hop_amount_in)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        amount_received >= minimum_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    settle_route(
+        ctx.accounts,
+        ctx.bumps.authority,
+        &hops,
+        amount_in,
+        &hop_outputs,
+        native_sol,
+        hook_accounts,
+    )
+}
+
+/// Exact output over several pools: walk the route backwards from
+/// `amount_out`, rounding every hop's required input up in favour of the pool
+pub fn swap_route_base_output<'info>(
+    ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+    amount_out: u64,
+    maximum_amount_in: u64,
+    native_sol: bool,
+) -> Result<()> {
+    require!(amount_out > 0, ErrorCode::InvalidTokenAmount);
+    require!(maximum_amount_in > 0, ErrorCode::InvalidTokenAmount);
+    // Each hop checks its own pool, the global pause covers them all
+    require!(
+        ctx.accounts
+            .admin_state
+            .is_enabled(PoolStatusBitIndex::Swap),
+        ErrorCode::SwapBlocked
+    );
+
+    let (mut hops, hook_accounts) = load_route(ctx.accounts, ctx.remaining_accounts)?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let epoch = Clock::get()?.epoch;
+
+    // Amount the current hop's output vault must send; the last one covers
+    // the output transfer fee so the user receives exactly amount_out
+    let mut hop_amount_out = amount_out
+        .checked_add(get_transfer_inverse_fee(
+            &hops[hops.len() - 1].output_mint,
+            amount_out,
+        )?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let mut hop_outputs = vec![0u64; hops.len()];
+    for index in (0..hops.len()).rev() {
+        let (previous_hops, remaining_hops) = hops.split_at_mut(index);
+        let input_mint = match previous_hops.last() {
+            Some(previous_hop) => &previous_hop.output_mint,
+            None => &ctx.accounts.input_token_mint,
+        };
+        let hop = &mut remaining_hops[0];
+        let is_token_0_input = hop.is_token_0_input();
+        let (clean_vault_0, clean_vault_1) = hop.open(block_timestamp)?;
+        let (input_vault_balance, output_vault_balance) = if is_token_0_input {
+            (clean_vault_0 as u128, clean_vault_1 as u128)
+        } else {
+            (clean_vault_1 as u128, clean_vault_0 as u128)
+        };
+
+        let amm_config = &hop.amm_config;
+        let trade_fee_rate = hop.pool_state.trade_fee_rate(amm_config, block_timestamp)?;
+        // The creator fee is a separate surcharge on the input or the output
+        let is_creator_fee_on_input = hop.pool_state.is_creator_fee_on_input(is_token_0_input);
+        let (input_creator_fee_rate, output_creator_fee_rate) = hop
+            .pool_state
+            .creator_fee_rates(amm_config, is_token_0_input);
+        // An output-side creator fee leaves the reserve on top of what is sent
+        let output_amount = amount_with_fees(hop_amount_out as u128, 0, output_creator_fee_rate)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let creator_fee_out = output_amount
+            .checked_sub(hop_amount_out as u128)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let input_without_fee =
+            swap_base_output_without_fees(output_amount, input_vault_balance, output_vault_balance)
+                .ok_or(ErrorCode::MathOverflow)?;
+        let vault_amount_in =
+            amount_with_fees(input_without_fee, trade_fee_rate, input_creator_fee_rate)
+                .ok_or(ErrorCode::MathOverflow)?;
+        let fee = trade_fee(vault_amount_in, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+        let creator_fee_in =
+            creator_fee(vault_amount_in, input_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+        let protocol_fee_amount = protocol_fee(fee, hop.pool_state.protocol_fee_rate(amm_config))
+            .ok_or(ErrorCode::MathOverflow)?;
+        let fund_fee_amount = fund_fee(fee, hop.pool_state.fund_fee_rate(amm_config))
+            .ok_or(ErrorCode::MathOverflow)?;
+
+        // k must not shrink with what actually stays after the fee
+        let constant_before = input_vault_balance
+            .checked_mul(output_vault_balance)
+            .ok_or(ErrorCode::MathOverflow)?;
+        let constant_after = input_vault_balance
+            .checked_add(vault_amount_in)
+            .and_then(|amount| amount.checked_sub(fee))
+            .and_then(|amount| amount.checked_sub(creator_fee_in))
+            .ok_or(ErrorCode::MathOverflow)?
+            .checked_mul(
+                output_vault_balance
+                    .checked_sub(output_amount)
+                    .ok_or(ErrorCode::MathOverflow)?,
+            )
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            constant_after >= constant_before,
+            ErrorCode::ConstantProductInvariant
+        );
+
+        let vault_amount_in: u64 = vault_amount_in
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        // Whoever feeds this hop also covers the input transfer fee
+        let transfer_fee_in = get_transfer_inverse_fee(input_mint, vault_amount_in)?;
+        let pool_state = &mut hop.pool_state;
+        let (creator_fee_in, creator_fee_out) =
+            pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
+        pool_state.accrue_fees(
+            is_token_0_input,
+            protocol_fee_amount as u64,
+            fund_fee_amount as u64,
+            creator_fee_in as u64,
+        )?;
+        pool_state.accrue_fees(!is_token_0_input, 0, 0, creator_fee_out as u64)?;
+        pool_state.recent_epoch = epoch;
+
+        // The LP share of the trade fee stays in the input reserve
+        let input_reserve_after: u64 = input_vault_balance
+            .checked_add(vault_amount_in as u128)
+            .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+            .and_then(|amount| amount.checked_sub(fund_fee_amount))
+            .and_then(|amount| amount.checked_sub(creator_fee_in))
+            .ok_or(ErrorCode::MathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        let output_reserve_after: u64 = output_vault_balance
+            .checked_sub(hop_amount_out as u128)
+            .and_then(|amount| amount.checked_sub(creator_fee_out))
+            .ok_or(ErrorCode::MathOverflow)?
+            .try_into()
+            .map_err(|_| ErrorCode::MathOverflow)?;
+        let (reserve_0_after, reserve_1_after) = if is_token_0_input {
+            (input_reserve_after, output_reserve_after)
+        } else {
+            (output_reserve_after, input_reserve_after)
+        };
+        pool_state.update_volatility(
+            &hop.amm_config,
+            (clean_vault_0, clean_vault_1),
+            (reserve_0_after, reserve_1_after),
+            block_timestamp,
+        );
+        pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+        let transfer_fee_out = get_transfer_fee(&hop.output_mint, hop_amount_out)?;
+        let hop_amount_in = vault_amount_in
+            .checked_add(transfer_fee_in)
+            .ok_or(ErrorCode::MathOverflow)?;
+        emit!(SwapEvent {
+            pool_id: pool_state.key(),
+            user: ctx.accounts.signer.key(),
+            is_token_0_input,
+            base_input: false,
+            amount_in: hop_amount_in,
+            amount_out: hop_amount_out - transfer_fee_out,
+            input_transfer_fee: transfer_fee_in,
+            output_transfer_fee: transfer_fee_out,
+            trade_fee_rate,
+            trade_fee: fee as u64,
+            protocol_fee: protocol_fee_amount as u64,
+            fund_fee: fund_fee_amount as u64,
+            is_creator_fee_on_input,
+            creator_fee: (creator_fee_in + creator_fee_out) as u64,
+            reserve_0_before: clean_vault_0,
+            reserve_1_before: clean_vault_1,
+            reserve_0_after,
+            reserve_1_after,
+            lp_supply: pool_state.lp_supply,
+        });
+
+        hop_outputs[index] = hop_amount_out;
+        hop_amount_out = hop_amount_in;
+    }
+
+    // What the first hop needs is what the user sends
+    let amount_in = hop_amount_out;
+    require!(amount_in <= maximum_amount_in, ErrorCode::SlippageExceeded);
+
+    settle_route(
+        ctx.accounts,
+        ctx.bumps.authority,
+        &hops,
+        amount_in,
+        &hop_outputs,
+        native_sol,
+        hook_accounts,
+    )
+}
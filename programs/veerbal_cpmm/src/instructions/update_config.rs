@@ -0,0 +1,117 @@
+use crate::{
+    constants::{ADMIN_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    instructions::CONFIG_SEED,
+    states::{AdminState, ConfigUpdatedEvent, Role, RoleRegistry},
+    AmmConfig,
+};
+use anchor_lang::prelude::*;
+
+/// Every field is optional so each setting can be changed on its own
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdateAmmConfigParams {
+    pub trade_fee_rate: Option<u64>,
+    pub protocol_fee_rate: Option<u64>,
+    pub fund_fee_rate: Option<u64>,
+    pub creator_fee_rate: Option<u64>,
+    pub create_pool_fee: Option<u64>,
+    pub protocol_owner: Option<Pubkey>,
+    pub fund_owner: Option<Pubkey>,
+    pub disable_create_pool: Option<bool>,
+    pub dynamic_fee_enabled: Option<bool>,
+    pub min_trade_fee_rate: Option<u64>,
+    pub max_trade_fee_rate: Option<u64>,
+    pub volatility_decay_period: Option<u64>,
+    pub volatility_fee_factor: Option<u64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdateAmmConfig<'info> {
+    #[account(constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::FeeManager, &owner.key()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(mut, seeds=[CONFIG_SEED, amm_config.index.to_be_bytes().as_ref()], bump = amm_config.bump)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+pub fn update_amm_config(
+    ctx: Context<UpdateAmmConfig>,
+    params: UpdateAmmConfigParams,
+) -> Result<()> {
+    // Fee managers only touch fees and receivers, pool creation stays with the admin
+    require!(
+        params.disable_create_pool.is_none()
+            || ctx.accounts.owner.key() == ctx.accounts.admin_state.admin,
+        ErrorCode::InvalidOwner
+    );
+
+    let amm_config = &mut ctx.accounts.amm_config;
+
+    if let Some(trade_fee_rate) = params.trade_fee_rate {
+        amm_config.trade_fee_rate = trade_fee_rate;
+    }
+    if let Some(protocol_fee_rate) = params.protocol_fee_rate {
+        amm_config.protocol_fee_rate = protocol_fee_rate;
+    }
+    if let Some(fund_fee_rate) = params.fund_fee_rate {
+        amm_config.fund_fee_rate = fund_fee_rate;
+    }
+    if let Some(creator_fee_rate) = params.creator_fee_rate {
+        amm_config.creator_fee_rate = creator_fee_rate;
+    }
+    if let Some(create_pool_fee) = params.create_pool_fee {
+        amm_config.create_pool_fee = create_pool_fee;
+    }
+    if let Some(protocol_owner) = params.protocol_owner {
+        amm_config.protocol_owner = protocol_owner;
+    }
+    if let Some(fund_owner) = params.fund_owner {
+        amm_config.fund_owner = fund_owner;
+    }
+    if let Some(disable_create_pool) = params.disable_create_pool {
+        amm_config.disable_create_pool = disable_create_pool;
+    }
+    if let Some(dynamic_fee_enabled) = params.dynamic_fee_enabled {
+        amm_config.dynamic_fee_enabled = dynamic_fee_enabled;
+    }
+    if let Some(min_trade_fee_rate) = params.min_trade_fee_rate {
+        amm_config.min_trade_fee_rate = min_trade_fee_rate;
+    }
+    if let Some(max_trade_fee_rate) = params.max_trade_fee_rate {
+        amm_config.max_trade_fee_rate = max_trade_fee_rate;
+    }
+    if let Some(volatility_decay_period) = params.volatility_decay_period {
+        amm_config.volatility_decay_period = volatility_decay_period;
+    }
+    if let Some(volatility_fee_factor) = params.volatility_fee_factor {
+        amm_config.volatility_fee_factor = volatility_fee_factor;
+    }
+
+    // Validate the resulting config, not just the fields that changed
+    amm_config.check_fee_rates()?;
+
+    emit!(ConfigUpdatedEvent {
+        amm_config: amm_config.key(),
+        trade_fee_rate: amm_config.trade_fee_rate,
+        creator_fee_rate: amm_config.creator_fee_rate,
+        protocol_fee_rate: amm_config.protocol_fee_rate,
+        fund_fee_rate: amm_config.fund_fee_rate,
+        create_pool_fee: amm_config.create_pool_fee,
+        protocol_owner: amm_config.protocol_owner,
+        fund_owner: amm_config.fund_owner,
+        disable_create_pool: amm_config.disable_create_pool,
+        dynamic_fee_enabled: amm_config.dynamic_fee_enabled,
+        min_trade_fee_rate: amm_config.min_trade_fee_rate,
+        max_trade_fee_rate: amm_config.max_trade_fee_rate,
+        volatility_decay_period: amm_config.volatility_decay_period,
+        volatility_fee_factor: amm_config.volatility_fee_factor,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use crate::{
+    constants::{ADMIN_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    instructions::CONFIG_SEED,
+    states::{AdminState, ConfigStatusUpdatedEvent, Role, RoleRegistry, POOL_STATUS_MASK},
+    AmmConfig,
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateConfigStatus<'info> {
+    #[account(constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::Pauser, &owner.key()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(mut, seeds=[CONFIG_SEED, amm_config.index.to_be_bytes().as_ref()], bump = amm_config.bump)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+}
+
+/// A set bit disables the matching `PoolStatusBitIndex` action on every pool
+/// under this config
+pub fn update_config_status(ctx: Context<UpdateConfigStatus>, status: u8) -> Result<()> {
+    require!(
+        status & !POOL_STATUS_MASK == 0,
+        ErrorCode::InvalidPoolStatus
+    );
+
+    let amm_config = &mut ctx.accounts.amm_config;
+    let old_status = amm_config.status;
+    amm_config.status = status;
+
+    emit!(ConfigStatusUpdatedEvent {
+        amm_config: amm_config.key(),
+        old_status,
+        new_status: status,
+        updated_by: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
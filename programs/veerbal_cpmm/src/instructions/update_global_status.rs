@@ -0,0 +1,39 @@
+use crate::{
+    constants::{ADMIN_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    states::{AdminState, GlobalStatusUpdatedEvent, Role, RoleRegistry, POOL_STATUS_MASK},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateGlobalStatus<'info> {
+    #[account(constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::Pauser, &owner.key()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+}
+
+/// A set bit disables the matching `PoolStatusBitIndex` action on every pool,
+/// e.g. pause deposits and swaps but leave withdrawals open
+pub fn update_global_status(ctx: Context<UpdateGlobalStatus>, status: u8) -> Result<()> {
+    require!(
+        status & !POOL_STATUS_MASK == 0,
+        ErrorCode::InvalidPoolStatus
+    );
+
+    let admin_state = &mut ctx.accounts.admin_state;
+    let old_status = admin_state.status;
+    admin_state.status = status;
+
+    emit!(GlobalStatusUpdatedEvent {
+        old_status,
+        new_status: status,
+        updated_by: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
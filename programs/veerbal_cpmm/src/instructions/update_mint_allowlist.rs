@@ -0,0 +1,88 @@
+use crate::{
+    constants::{ADMIN_SEED, MINT_ALLOWLIST_SEED},
+    error::ErrorCode,
+    states::{
+        AdminState, MintAllowlist, MintAllowlistUpdatedEvent, MAX_ALLOWLISTED_HOOK_PROGRAMS,
+        MAX_ALLOWLISTED_MINTS,
+    },
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdateMintAllowlist<'info> {
+    #[account(mut, address = admin_state.admin @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(init_if_needed, seeds=[MINT_ALLOWLIST_SEED], bump, payer = owner, space = MintAllowlist::LEN)]
+    pub mint_allowlist: Box<Account<'info, MintAllowlist>>,
+
+    pub system_program: Program<'info, System>,
+}
+
+pub fn update_mint_allowlist(
+    ctx: Context<UpdateMintAllowlist>,
+    mint: Pubkey,
+    allowed: bool,
+) -> Result<()> {
+    let mint_allowlist = &mut ctx.accounts.mint_allowlist;
+    mint_allowlist.bump = ctx.bumps.mint_allowlist;
+
+    update_entry(
+        &mut mint_allowlist.mints,
+        mint,
+        allowed,
+        MAX_ALLOWLISTED_MINTS,
+    )?;
+
+    emit!(MintAllowlistUpdatedEvent {
+        key: mint,
+        is_transfer_hook_program: false,
+        allowed,
+    });
+
+    Ok(())
+}
+
+pub fn update_transfer_hook_allowlist(
+    ctx: Context<UpdateMintAllowlist>,
+    program_id: Pubkey,
+    allowed: bool,
+) -> Result<()> {
+    let mint_allowlist = &mut ctx.accounts.mint_allowlist;
+    mint_allowlist.bump = ctx.bumps.mint_allowlist;
+
+    update_entry(
+        &mut mint_allowlist.transfer_hook_programs,
+        program_id,
+        allowed,
+        MAX_ALLOWLISTED_HOOK_PROGRAMS,
+    )?;
+
+    emit!(MintAllowlistUpdatedEvent {
+        key: program_id,
+        is_transfer_hook_program: true,
+        allowed,
+    });
+
+    Ok(())
+}
+
+fn update_entry(
+    entries: &mut Vec<Pubkey>,
+    key: Pubkey,
+    allowed: bool,
+    max_len: usize,
+) -> Result<()> {
+    if allowed {
+        if !entries.contains(&key) {
+            require!(entries.len() < max_len, ErrorCode::AllowlistFull);
+            entries.push(key);
+        }
+    } else {
+        entries.retain(|entry| *entry != key);
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use crate::{
+    constants::POOL_SEED,
+    error::ErrorCode,
+    states::{PoolCreatorUpdatedEvent, PoolState},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolCreator<'info> {
+    #[account(address = pool_state.pool_creator @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+}
+
+#[derive(Accounts)]
+pub struct AcceptPoolCreator<'info> {
+    #[account(address = pool_state.pending_pool_creator @ ErrorCode::InvalidOwner)]
+    pub new_creator: Signer<'info>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+}
+
+/// Nominate a new creator, who must accept before the role moves.
+/// Proposing the default pubkey cancels a pending transfer
+pub fn propose_pool_creator(ctx: Context<UpdatePoolCreator>, new_creator: Pubkey) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.pending_pool_creator = new_creator;
+
+    emit_pool_creator_updated(pool_state);
+    Ok(())
+}
+
+pub fn accept_pool_creator(ctx: Context<AcceptPoolCreator>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.pool_creator = pool_state.pending_pool_creator;
+    pool_state.pending_pool_creator = Pubkey::default();
+
+    emit_pool_creator_updated(pool_state);
+    Ok(())
+}
+
+/// Stop booking creator fees for good, later surcharges stay in the
+/// reserves for LPs. Fees already accrued can still be collected
+pub fn renounce_pool_creator(ctx: Context<UpdatePoolCreator>) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    pool_state.creator_fee_active = false;
+    pool_state.pending_pool_creator = Pubkey::default();
+
+    emit_pool_creator_updated(pool_state);
+    Ok(())
+}
+
+fn emit_pool_creator_updated(pool_state: &Account<PoolState>) {
+    emit!(PoolCreatorUpdatedEvent {
+        pool_id: pool_state.key(),
+        pool_creator: pool_state.pool_creator,
+        pending_pool_creator: pool_state.pending_pool_creator,
+        creator_fee_active: pool_state.creator_fee_active,
+    });
+}
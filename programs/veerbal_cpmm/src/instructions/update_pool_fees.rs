@@ -0,0 +1,56 @@
+use crate::{
+    constants::{ADMIN_SEED, POOL_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    states::{AdminState, PoolFeesUpdatedEvent, PoolState, Role, RoleRegistry},
+    AmmConfig,
+};
+use anchor_lang::prelude::*;
+
+/// Replaces every override at once: None clears a rate back to the
+/// AmmConfig, so pass the current override of any rate that isn't changing
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default)]
+pub struct UpdatePoolFeesParams {
+    pub trade_fee_rate: Option<u64>,
+    pub protocol_fee_rate: Option<u64>,
+    pub fund_fee_rate: Option<u64>,
+    pub creator_fee_rate: Option<u64>,
+}
+
+#[derive(Accounts)]
+pub struct UpdatePoolFees<'info> {
+    #[account(constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::FeeManager, &owner.key()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+}
+
+pub fn update_pool_fees(ctx: Context<UpdatePoolFees>, params: UpdatePoolFeesParams) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+
+    pool_state.trade_fee_rate_override = params.trade_fee_rate;
+    pool_state.protocol_fee_rate_override = params.protocol_fee_rate;
+    pool_state.fund_fee_rate_override = params.fund_fee_rate;
+    pool_state.creator_fee_rate_override = params.creator_fee_rate;
+
+    pool_state.check_fee_rates(&ctx.accounts.amm_config)?;
+
+    emit!(PoolFeesUpdatedEvent {
+        pool_id: pool_state.key(),
+        trade_fee_rate: params.trade_fee_rate,
+        protocol_fee_rate: params.protocol_fee_rate,
+        fund_fee_rate: params.fund_fee_rate,
+        creator_fee_rate: params.creator_fee_rate,
+    });
+
+    Ok(())
+}
@@ -0,0 +1,39 @@
+use crate::{
+    constants::{ADMIN_SEED, POOL_SEED, ROLE_REGISTRY_SEED},
+    error::ErrorCode,
+    states::{AdminState, PoolState, PoolStatusUpdatedEvent, Role, RoleRegistry, POOL_STATUS_MASK},
+};
+use anchor_lang::prelude::*;
+
+#[derive(Accounts)]
+pub struct UpdatePoolStatus<'info> {
+    #[account(constraint = owner.key() == admin_state.admin || role_registry.has_role(Role::Pauser, &owner.key()) @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(seeds=[ROLE_REGISTRY_SEED], bump = role_registry.bump)]
+    pub role_registry: Box<Account<'info, RoleRegistry>>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+}
+
+/// A set bit disables the matching `PoolStatusBitIndex` action
+pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
+    require!(status & !POOL_STATUS_MASK == 0, ErrorCode::InvalidPoolStatus);
+
+    let pool_state = &mut ctx.accounts.pool_state;
+    let old_status = pool_state.status;
+    pool_state.status = status;
+
+    emit!(PoolStatusUpdatedEvent {
+        pool_id: pool_state.key(),
+        old_status,
+        new_status: status,
+        updated_by: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
@@ -0,0 +1,220 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token::Token,
+    token_interface::{self, CloseAccount, Mint, TokenAccount},
+};
+
+use crate::{
+    constants::{AUTH_SEED, LP_MINT_SEED, LP_VESTING_SEED, LP_VESTING_VAULT_SEED, POOL_SEED},
+    error::ErrorCode,
+    states::{
+        LpVesting, LpVestingClosedEvent, LpVestingCreatedEvent, PoolState, VestedLpClaimedEvent,
+    },
+    utils::{transfer_from_pool_vault_to_user, transfer_from_user_to_pool_vault},
+};
+
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct LockLpVesting<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    /// CHECK: Only used as the key the escrow vests to
+    pub beneficiary: UncheckedAccount<'info>,
+
+    #[account(seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = signer, token::token_program = token_program)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // Keyed by funder and nonce so nobody else can squat a beneficiary's escrow
+    #[account(init, payer = signer, seeds=[LP_VESTING_SEED, pool_state.key().as_ref(), beneficiary.key().as_ref(), signer.key().as_ref(), nonce.to_be_bytes().as_ref()], bump, space = LpVesting::LEN)]
+    pub lp_vesting: Box<Account<'info, LpVesting>>,
+
+    #[account(init, payer = signer, seeds=[LP_VESTING_VAULT_SEED, lp_vesting.key().as_ref()], bump, token::mint = lp_mint, token::authority = authority, token::token_program = token_program)]
+    pub lp_vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+#[derive(Accounts)]
+pub struct ClaimVestedLp<'info> {
+    #[account(address = lp_vesting.beneficiary @ ErrorCode::InvalidOwner)]
+    pub beneficiary: Signer<'info>,
+
+    #[account(seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(mut, seeds=[LP_VESTING_SEED, pool_state.key().as_ref(), beneficiary.key().as_ref(), lp_vesting.funder.as_ref(), lp_vesting.nonce.to_be_bytes().as_ref()], bump = lp_vesting.bump)]
+    pub lp_vesting: Box<Account<'info, LpVesting>>,
+
+    #[account(mut, seeds=[LP_VESTING_VAULT_SEED, lp_vesting.key().as_ref()], bump)]
+    pub lp_vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = beneficiary, token::token_program = token_program)]
+    pub beneficiary_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+#[derive(Accounts)]
+pub struct CloseLpVesting<'info> {
+    #[account(constraint = owner.key() == lp_vesting.beneficiary || owner.key() == lp_vesting.funder @ ErrorCode::InvalidOwner)]
+    pub owner: Signer<'info>,
+
+    /// CHECK: Rent goes back to the funder, who paid it
+    #[account(mut, address = lp_vesting.funder @ ErrorCode::InvalidOwner)]
+    pub funder: UncheckedAccount<'info>,
+
+    #[account(seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(mut, close = funder, seeds=[LP_VESTING_SEED, pool_state.key().as_ref(), lp_vesting.beneficiary.as_ref(), lp_vesting.funder.as_ref(), lp_vesting.nonce.to_be_bytes().as_ref()], bump = lp_vesting.bump)]
+    pub lp_vesting: Box<Account<'info, LpVesting>>,
+
+    #[account(mut, seeds=[LP_VESTING_VAULT_SEED, lp_vesting.key().as_ref()], bump)]
+    pub lp_vesting_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Escrow LP for a beneficiary. Vesting starts now, or at the pool's
+/// `open_time` if that is later, and a cliff before the start is moved up to it
+pub fn lock_lp_vesting(
+    ctx: Context<LockLpVesting>,
+    nonce: u64,
+    lp_amount: u64,
+    mut cliff_time: u64,
+    end_time: u64,
+) -> Result<()> {
+    require!(lp_amount > 0, ErrorCode::InvalidLPAmount);
+
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let start_time = ctx.accounts.pool_state.open_time.max(block_timestamp);
+    if cliff_time < start_time {
+        cliff_time = start_time
+    }
+    require!(end_time >= cliff_time, ErrorCode::InvalidVestingSchedule);
+
+    transfer_from_user_to_pool_vault(
+        ctx.accounts.signer.to_account_info(),
+        ctx.accounts.signer_lp.to_account_info(),
+        ctx.accounts.lp_vesting_vault.to_account_info(),
+        &ctx.accounts.lp_mint,
+        ctx.accounts.token_program.to_account_info(),
+        lp_amount,
+        &[],
+    )?;
+
+    let lp_vesting = &mut ctx.accounts.lp_vesting;
+    lp_vesting.bump = ctx.bumps.lp_vesting;
+    lp_vesting.pool_id = ctx.accounts.pool_state.key();
+    lp_vesting.beneficiary = ctx.accounts.beneficiary.key();
+    lp_vesting.funder = ctx.accounts.signer.key();
+    lp_vesting.nonce = nonce;
+    lp_vesting.total_amount = lp_amount;
+    lp_vesting.claimed_amount = 0;
+    lp_vesting.start_time = start_time;
+    lp_vesting.cliff_time = cliff_time;
+    lp_vesting.end_time = end_time;
+
+    emit!(LpVestingCreatedEvent {
+        pool_id: lp_vesting.pool_id,
+        lp_vesting: lp_vesting.key(),
+        beneficiary: lp_vesting.beneficiary,
+        funder: lp_vesting.funder,
+        lp_amount,
+        start_time,
+        cliff_time,
+        end_time,
+    });
+
+    Ok(())
+}
+
+pub fn claim_vested_lp(ctx: Context<ClaimVestedLp>) -> Result<()> {
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+
+    let lp_vesting = &mut ctx.accounts.lp_vesting;
+    let lp_amount = lp_vesting
+        .claimable_amount(block_timestamp)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(lp_amount > 0, ErrorCode::NoVestedLp);
+
+    lp_vesting.claimed_amount = lp_vesting
+        .claimed_amount
+        .checked_add(lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let seeds = &[AUTH_SEED, &[ctx.accounts.pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.lp_vesting_vault.to_account_info(),
+        ctx.accounts.beneficiary_lp.to_account_info(),
+        &ctx.accounts.lp_mint,
+        ctx.accounts.token_program.to_account_info(),
+        lp_amount,
+        signer_seeds,
+        &[],
+    )?;
+
+    emit!(VestedLpClaimedEvent {
+        pool_id: lp_vesting.pool_id,
+        lp_vesting: lp_vesting.key(),
+        beneficiary: lp_vesting.beneficiary,
+        lp_amount,
+        claimed_amount: lp_vesting.claimed_amount,
+    });
+
+    Ok(())
+}
+
+/// Close a fully claimed escrow and its vault, rent goes back to the funder
+pub fn close_lp_vesting(ctx: Context<CloseLpVesting>) -> Result<()> {
+    let lp_vesting = &ctx.accounts.lp_vesting;
+    require!(
+        lp_vesting.claimed_amount == lp_vesting.total_amount,
+        ErrorCode::LpVestingNotClaimed
+    );
+
+    let seeds = &[AUTH_SEED, &[ctx.accounts.pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+    token_interface::close_account(CpiContext::new_with_signer(
+        ctx.accounts.token_program.to_account_info(),
+        CloseAccount {
+            account: ctx.accounts.lp_vesting_vault.to_account_info(),
+            destination: ctx.accounts.funder.to_account_info(),
+            authority: ctx.accounts.authority.to_account_info(),
+        },
+        signer_seeds,
+    ))?;
+
+    emit!(LpVestingClosedEvent {
+        pool_id: lp_vesting.pool_id,
+        lp_vesting: lp_vesting.key(),
+        closed_by: ctx.accounts.owner.key(),
+    });
+
+    Ok(())
+}
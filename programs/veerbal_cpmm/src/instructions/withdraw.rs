@@ -1,13 +1,18 @@
 use crate::{
-    constants::{AUTH_SEED, LP_MINT_SEED, POOL_SEED},
+    constants::{ADMIN_SEED, AUTH_SEED, LP_MINT_SEED, POOL_SEED},
     error::ErrorCode,
 };
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Burn, Mint, Token, TokenAccount, Transfer};
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface},
+};
 
 use crate::{
     instructions::CONFIG_SEED,
-    states::{PoolState, PoolStatusBitIndex},
+    states::{AdminState, LpChangeEvent, LpChangeType, PoolState, PoolStatusBitIndex},
+    utils::{get_transfer_fee, is_native_mint, transfer_from_pool_vault_to_user, unwrap_native_sol},
     AmmConfig,
 };
 
@@ -20,48 +25,68 @@ pub struct Withdraw<'info> {
     #[account(mut, seeds=[POOL_SEED, amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
     pub pool_state: Box<Account<'info, PoolState>>,
 
-    // User will provide these accounts
-    #[account(mut, token::mint = pool_state.token_0_mint, token::authority = signer)]
-    pub signer_token_0: Box<Account<'info, TokenAccount>>,
+    #[account(address = pool_state.token_0_mint @ ErrorCode::MintMismatch, mint::token_program = token_0_program)]
+    pub token_0_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    #[account(address = pool_state.token_1_mint @ ErrorCode::MintMismatch, mint::token_program = token_1_program)]
+    pub token_1_mint: Box<InterfaceAccount<'info, Mint>>,
 
-    #[account(mut, token::mint = pool_state.token_1_mint, token::authority = signer)]
-    pub signer_token_1: Box<Account<'info, TokenAccount>>,
+    // User token accounts, created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = token_0_mint, associated_token::authority = signer, associated_token::token_program = token_0_program)]
+    pub signer_token_0: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(init_if_needed, payer = signer, associated_token::mint = token_1_mint, associated_token::authority = signer, associated_token::token_program = token_1_program)]
+    pub signer_token_1: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, constraint = token_0_vault.key() == pool_state.token_0_vault @ ErrorCode::InvalidVault, token::mint = pool_state.token_0_mint, token::authority = authority)]
-    pub token_0_vault: Box<Account<'info, TokenAccount>>,
+    pub token_0_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, constraint = token_1_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, token::mint = pool_state.token_1_mint, token::authority = authority)]
-    pub token_1_vault: Box<Account<'info, TokenAccount>>,
+    pub token_1_vault: Box<InterfaceAccount<'info, TokenAccount>>,
 
-    #[account(mut, token::mint = lp_mint, token::authority = signer)]
-    pub signer_lp: Box<Account<'info, TokenAccount>>,
+    #[account(mut, token::mint = lp_mint, token::authority = signer, token::token_program = token_program)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
 
     #[account(mut, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
-    pub lp_mint: Account<'info, Mint>,
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
 
     // pool state dependent upon amm config
     #[account(seeds=[CONFIG_SEED, amm_config.index.to_be_bytes().as_ref()], bump = amm_config.bump)]
     pub amm_config: Box<Account<'info, AmmConfig>>,
 
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
     // Authority pda is needed to sign vault transactions
     /// CHECK: This is not dangerous because we don't read or write from this account
     #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
     pub authority: UncheckedAccount<'info>,
 
     pub token_program: Program<'info, Token>,
+    #[account(address = pool_state.token_0_program)]
+    pub token_0_program: Interface<'info, TokenInterface>,
+    #[account(address = pool_state.token_1_program)]
+    pub token_1_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
 }
 
-pub fn withdraw(
-    ctx: Context<Withdraw>,
+pub fn withdraw<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
     lp_amount: u64,
     minimum_token_0_amount: u64,
     minimum_token_1_amount: u64,
+    native_sol: bool,
 ) -> Result<()> {
     let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
 
     // 1.  VALIDATION
     require!(
-        pool_state.is_enabled(PoolStatusBitIndex::Withdraw),
+        pool_state.is_enabled(PoolStatusBitIndex::Withdraw)
+            && amm_config.is_enabled(PoolStatusBitIndex::Withdraw)
+            && admin_state.is_enabled(PoolStatusBitIndex::Withdraw),
         ErrorCode::WithdrawDisabled
     );
 
@@ -76,7 +101,7 @@ pub fn withdraw(
         ctx.accounts.token_1_vault.amount,
     )?;
 
-    let token_0_amount = (((lp_amount as u128)
+    let token_0_amount: u64 = (((lp_amount as u128)
         .checked_mul(clean_vault_0 as u128)
         .ok_or(ErrorCode::MathOverflow)?)
     .checked_div(pool_state.lp_supply as u128)
@@ -84,7 +109,7 @@ pub fn withdraw(
     .try_into()
     .map_err(|_| ErrorCode::MathOverflow)?;
 
-    let token_1_amount = (((lp_amount as u128)
+    let token_1_amount: u64 = (((lp_amount as u128)
         .checked_mul(clean_vault_1 as u128)
         .ok_or(ErrorCode::MathOverflow)?)
     .checked_div(pool_state.lp_supply as u128)
@@ -95,14 +120,21 @@ pub fn withdraw(
     require!(token_0_amount > 0, ErrorCode::ZeroTradingTokens);
     require!(token_1_amount > 0, ErrorCode::ZeroTradingTokens);
 
-    // 3. SLIPPAGE CHECK
+    // 3. SLIPPAGE CHECK (on what the user receives after transfer fees)
+    let token_0_received = token_0_amount
+        .checked_sub(get_transfer_fee(&ctx.accounts.token_0_mint, token_0_amount)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let token_1_received = token_1_amount
+        .checked_sub(get_transfer_fee(&ctx.accounts.token_1_mint, token_1_amount)?)
+        .ok_or(ErrorCode::MathOverflow)?;
+
     require!(
-        token_0_amount >= minimum_token_0_amount,
+        token_0_received >= minimum_token_0_amount,
         ErrorCode::SlippageExceeded
     );
 
     require!(
-        token_1_amount >= minimum_token_1_amount,
+        token_1_received >= minimum_token_1_amount,
         ErrorCode::SlippageExceeded
     );
 
@@ -120,33 +152,80 @@ pub fn withdraw(
 
     let cpi_context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
 
-    token::burn(cpi_context, lp_amount)?;
+    token_interface::burn(cpi_context, lp_amount)?;
 
     // 5.  INTERACTIONS LAST
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.token_0_vault.to_account_info(),
-        to: ctx.accounts.signer_token_0.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
-
     let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
     let signer_seeds = &[&seeds[..]];
 
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
-    token::transfer(cpi_ctx, token_0_amount)?;
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_0_vault.to_account_info(),
+        ctx.accounts.signer_token_0.to_account_info(),
+        &ctx.accounts.token_0_mint,
+        ctx.accounts.token_0_program.to_account_info(),
+        token_0_amount,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
 
-    let cpi_accounts = Transfer {
-        from: ctx.accounts.token_1_vault.to_account_info(),
-        to: ctx.accounts.signer_token_1.to_account_info(),
-        authority: ctx.accounts.authority.to_account_info(),
-    };
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.token_1_vault.to_account_info(),
+        ctx.accounts.signer_token_1.to_account_info(),
+        &ctx.accounts.token_1_mint,
+        ctx.accounts.token_1_program.to_account_info(),
+        token_1_amount,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
 
-    let token_program = ctx.accounts.token_program.to_account_info();
-    let cpi_ctx = CpiContext::new_with_signer(token_program, cpi_accounts, signer_seeds);
-    token::transfer(cpi_ctx, token_1_amount)?;
+    // Unwrap the native side straight back to lamports
+    if native_sol && is_native_mint(&ctx.accounts.token_0_mint.key()) {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_0.to_account_info(),
+            ctx.accounts.token_0_program.to_account_info(),
+            ctx.accounts.signer_token_0.amount,
+        )?;
+    }
+    if native_sol && is_native_mint(&ctx.accounts.token_1_mint.key()) {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.signer_token_1.to_account_info(),
+            ctx.accounts.token_1_program.to_account_info(),
+            ctx.accounts.signer_token_1.amount,
+        )?;
+    }
 
     pool_state.recent_epoch = Clock::get()?.epoch;
+    let reserve_0_after = clean_vault_0
+        .checked_sub(token_0_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let reserve_1_after = clean_vault_1
+        .checked_sub(token_1_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+    pool_state.update_oracle(
+        reserve_0_after,
+        reserve_1_after,
+        Clock::get()?.unix_timestamp as u64,
+    );
+
+    emit!(LpChangeEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        change_type: LpChangeType::Withdraw,
+        lp_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee: token_0_amount - token_0_received,
+        token_1_transfer_fee: token_1_amount - token_1_received,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
 
     Ok(())
 }
@@ -0,0 +1,290 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token::Token,
+    token_interface::{self, Burn, Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ADMIN_SEED, AUTH_SEED, LP_MINT_SEED, POOL_SEED},
+    curve::{creator_fee, fund_fee, protocol_fee, swap_base_input_without_fees, trade_fee},
+    error::ErrorCode,
+    states::{AdminState, LpChangeEvent, LpChangeType, PoolState, PoolStatusBitIndex},
+    utils::{
+        get_transfer_fee, is_native_mint, transfer_from_pool_vault_to_user, unwrap_native_sol,
+    },
+    AmmConfig,
+};
+
+#[derive(Accounts)]
+pub struct WithdrawSingleToken<'info> {
+    #[account(mut)]
+    pub signer: Signer<'info>,
+
+    #[account(mut, seeds=[POOL_SEED, pool_state.amm_config.key().as_ref(), pool_state.token_0_mint.key().as_ref(), pool_state.token_1_mint.key().as_ref()], bump = pool_state.bump)]
+    pub pool_state: Box<Account<'info, PoolState>>,
+
+    #[account(address = pool_state.amm_config)]
+    pub amm_config: Box<Account<'info, AmmConfig>>,
+
+    #[account(seeds=[ADMIN_SEED], bump = admin_state.bump)]
+    pub admin_state: Box<Account<'info, AdminState>>,
+
+    #[account(address = output_vault.mint @ ErrorCode::MintMismatch, mint::token_program = output_token_program)]
+    pub output_token_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    // Created if missing so native SOL needs no wSOL account up front
+    #[account(init_if_needed, payer = signer, associated_token::mint = output_token_mint, associated_token::authority = signer, associated_token::token_program = output_token_program)]
+    pub output_token_account: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, constraint = output_vault.key() == pool_state.token_0_vault || output_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault)]
+    pub output_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    // The unwanted side is swapped back into the pool, nothing leaves this vault
+    #[account(constraint = input_vault.key() == pool_state.token_0_vault || input_vault.key() == pool_state.token_1_vault @ ErrorCode::InvalidVault, constraint = input_vault.key() != output_vault.key() @ ErrorCode::SameVault)]
+    pub input_vault: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, token::mint = lp_mint, token::authority = signer, token::token_program = token_program)]
+    pub signer_lp: Box<InterfaceAccount<'info, TokenAccount>>,
+
+    #[account(mut, seeds=[LP_MINT_SEED, pool_state.key().as_ref()], bump = pool_state.mint_bump)]
+    pub lp_mint: Box<InterfaceAccount<'info, Mint>>,
+
+    /// CHECK: This is not dangerous because we don't read or write from this account
+    #[account(seeds=[AUTH_SEED], bump = pool_state.auth_bump)]
+    pub authority: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub output_token_program: Interface<'info, TokenInterface>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/// Zap out: withdraw pro-rata, then swap the unwanted side back into the pool
+pub fn withdraw_single_token<'info>(
+    ctx: Context<'_, '_, 'info, 'info, WithdrawSingleToken<'info>>,
+    lp_amount: u64,
+    minimum_amount_out: u64,
+    native_sol: bool,
+) -> Result<()> {
+    let pool_state = &mut ctx.accounts.pool_state;
+    let amm_config = &ctx.accounts.amm_config;
+    let admin_state = &ctx.accounts.admin_state;
+
+    require!(
+        pool_state.is_enabled(PoolStatusBitIndex::Withdraw)
+            && amm_config.is_enabled(PoolStatusBitIndex::Withdraw)
+            && admin_state.is_enabled(PoolStatusBitIndex::Withdraw),
+        ErrorCode::WithdrawDisabled
+    );
+    // The swap leg trades against the pool, so a swap pause applies too
+    require!(
+        pool_state.is_enabled(PoolStatusBitIndex::Swap)
+            && amm_config.is_enabled(PoolStatusBitIndex::Swap)
+            && admin_state.is_enabled(PoolStatusBitIndex::Swap),
+        ErrorCode::SwapBlocked
+    );
+
+    require!(lp_amount > 0, ErrorCode::InvalidLPAmount);
+    require!(pool_state.lp_supply > 0, ErrorCode::PoolNotInitialized);
+
+    let is_token_0_output = ctx.accounts.output_vault.key() == pool_state.token_0_vault;
+    let (vault_0_amount, vault_1_amount) = if is_token_0_output {
+        (
+            ctx.accounts.output_vault.amount,
+            ctx.accounts.input_vault.amount,
+        )
+    } else {
+        (
+            ctx.accounts.input_vault.amount,
+            ctx.accounts.output_vault.amount,
+        )
+    };
+    let (clean_vault_0, clean_vault_1) =
+        pool_state.vault_amount_without_fee(vault_0_amount, vault_1_amount)?;
+    let block_timestamp = Clock::get()?.unix_timestamp as u64;
+    let (output_vault_balance, input_vault_balance) = if is_token_0_output {
+        (clean_vault_0 as u128, clean_vault_1 as u128)
+    } else {
+        (clean_vault_1 as u128, clean_vault_0 as u128)
+    };
+
+    // Withdraw leg: pro-rata share of both sides (round DOWN)
+    let lp_supply = pool_state.lp_supply as u128;
+    let output_share = (lp_amount as u128)
+        .checked_mul(output_vault_balance)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(lp_supply)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let input_share = (lp_amount as u128)
+        .checked_mul(input_vault_balance)
+        .ok_or(ErrorCode::MathOverflow)?
+        .checked_div(lp_supply)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(output_share > 0, ErrorCode::ZeroTradingTokens);
+    require!(input_share > 0, ErrorCode::ZeroTradingTokens);
+
+    // Swap leg: the unwanted share goes back in against the reduced reserves
+    let trade_fee_rate = pool_state.trade_fee_rate(&ctx.accounts.amm_config, block_timestamp)?;
+    let fee = trade_fee(input_share, trade_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let protocol_fee_amount = protocol_fee(fee, pool_state.protocol_fee_rate(amm_config))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let fund_fee_amount =
+        fund_fee(fee, pool_state.fund_fee_rate(amm_config)).ok_or(ErrorCode::MathOverflow)?;
+    // The creator fee is a separate surcharge on the input or the output
+    let (input_creator_fee_rate, output_creator_fee_rate) =
+        pool_state.creator_fee_rates(amm_config, !is_token_0_output);
+    let creator_fee_in =
+        creator_fee(input_share, input_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+    let actual_input = input_share
+        .checked_sub(fee)
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let swap_input_before = input_vault_balance
+        .checked_sub(input_share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let swap_output_before = output_vault_balance
+        .checked_sub(output_share)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let swap_output =
+        swap_base_input_without_fees(actual_input, swap_input_before, swap_output_before)
+            .ok_or(ErrorCode::MathOverflow)?;
+    let creator_fee_out =
+        creator_fee(swap_output, output_creator_fee_rate).ok_or(ErrorCode::MathOverflow)?;
+
+    let output_amount: u64 = output_share
+        .checked_add(swap_output)
+        .and_then(|amount| amount.checked_sub(creator_fee_out))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+
+    let (creator_fee_in, creator_fee_out) =
+        pool_state.booked_creator_fees(creator_fee_in, creator_fee_out);
+
+    // The withdraw leg is pro-rata, so the price moves from the reduced
+    // reserves to the reserves after the swap leg
+    let swap_input_after = swap_input_before
+        .checked_add(input_share)
+        .and_then(|amount| amount.checked_sub(protocol_fee_amount))
+        .and_then(|amount| amount.checked_sub(fund_fee_amount))
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?;
+    let swap_output_after = swap_output_before
+        .checked_sub(swap_output)
+        .ok_or(ErrorCode::MathOverflow)?;
+    let to_u64 = |amount: u128| u64::try_from(amount).map_err(|_| ErrorCode::MathOverflow);
+    let (input_before, output_before) = (to_u64(swap_input_before)?, to_u64(swap_output_before)?);
+    let (input_after, output_after) = (to_u64(swap_input_after)?, to_u64(swap_output_after)?);
+    let (reserves_before, reserves_after) = if is_token_0_output {
+        ((output_before, input_before), (output_after, input_after))
+    } else {
+        ((input_before, output_before), (input_after, output_after))
+    };
+    pool_state.update_volatility(
+        &ctx.accounts.amm_config,
+        reserves_before,
+        reserves_after,
+        block_timestamp,
+    );
+
+    pool_state.accrue_fees(
+        !is_token_0_output,
+        to_u64(protocol_fee_amount)?,
+        to_u64(fund_fee_amount)?,
+        to_u64(creator_fee_in)?,
+    )?;
+    pool_state.accrue_fees(is_token_0_output, 0, 0, to_u64(creator_fee_out)?)?;
+
+    // Slippage applies to what the user receives after the output transfer fee
+    let transfer_fee_out = get_transfer_fee(&ctx.accounts.output_token_mint, output_amount)?;
+    let amount_received = output_amount
+        .checked_sub(transfer_fee_out)
+        .ok_or(ErrorCode::MathOverflow)?;
+    require!(
+        amount_received >= minimum_amount_out,
+        ErrorCode::SlippageExceeded
+    );
+
+    pool_state.lp_supply = pool_state
+        .lp_supply
+        .checked_sub(lp_amount)
+        .ok_or(ErrorCode::MathOverflow)?;
+
+    let cpi_accounts = Burn {
+        mint: ctx.accounts.lp_mint.to_account_info(),
+        from: ctx.accounts.signer_lp.to_account_info(),
+        authority: ctx.accounts.signer.to_account_info(),
+    };
+    let cpi_context = CpiContext::new(ctx.accounts.token_program.to_account_info(), cpi_accounts);
+    token_interface::burn(cpi_context, lp_amount)?;
+
+    let seeds = &[AUTH_SEED, &[pool_state.auth_bump]];
+    let signer_seeds = &[&seeds[..]];
+    transfer_from_pool_vault_to_user(
+        ctx.accounts.authority.to_account_info(),
+        ctx.accounts.output_vault.to_account_info(),
+        ctx.accounts.output_token_account.to_account_info(),
+        &ctx.accounts.output_token_mint,
+        ctx.accounts.output_token_program.to_account_info(),
+        output_amount,
+        signer_seeds,
+        ctx.remaining_accounts,
+    )?;
+
+    if native_sol && is_native_mint(&ctx.accounts.output_token_mint.key()) {
+        unwrap_native_sol(
+            ctx.accounts.signer.to_account_info(),
+            ctx.accounts.output_token_account.to_account_info(),
+            ctx.accounts.output_token_program.to_account_info(),
+            ctx.accounts.output_token_account.amount,
+        )?;
+    }
+
+    pool_state.recent_epoch = Clock::get()?.epoch;
+
+    // Net effect on the vaults: only the chosen side is paid out, the
+    // rest of the change is fees moving out of the reserves
+    let output_reserve_after: u64 = output_vault_balance
+        .checked_sub(output_amount as u128)
+        .and_then(|amount| amount.checked_sub(creator_fee_out))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let input_reserve_after: u64 = input_vault_balance
+        .checked_sub(protocol_fee_amount)
+        .and_then(|amount| amount.checked_sub(fund_fee_amount))
+        .and_then(|amount| amount.checked_sub(creator_fee_in))
+        .ok_or(ErrorCode::MathOverflow)?
+        .try_into()
+        .map_err(|_| ErrorCode::MathOverflow)?;
+    let (token_0_amount, token_1_amount, token_0_transfer_fee, token_1_transfer_fee) =
+        if is_token_0_output {
+            (output_amount, 0, transfer_fee_out, 0)
+        } else {
+            (0, output_amount, 0, transfer_fee_out)
+        };
+    let (reserve_0_after, reserve_1_after) = if is_token_0_output {
+        (output_reserve_after, input_reserve_after)
+    } else {
+        (input_reserve_after, output_reserve_after)
+    };
+    pool_state.update_oracle(reserve_0_after, reserve_1_after, block_timestamp);
+    emit!(LpChangeEvent {
+        pool_id: pool_state.key(),
+        user: ctx.accounts.signer.key(),
+        change_type: LpChangeType::Withdraw,
+        lp_amount,
+        token_0_amount,
+        token_1_amount,
+        token_0_transfer_fee,
+        token_1_transfer_fee,
+        reserve_0_before: clean_vault_0,
+        reserve_1_before: clean_vault_1,
+        reserve_0_after,
+        reserve_1_after,
+        lp_supply: pool_state.lp_supply,
+    });
+
+    Ok(())
+}
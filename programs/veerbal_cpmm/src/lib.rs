@@ -7,14 +7,37 @@ pub mod curve;
 pub mod error;
 pub mod instructions;
 pub mod states;
+pub mod utils;
 
 declare_id!("C6TCz92bpYjWgty9mwrAoNh7u6RSdmyBRB4dMoBGgMrA");
+/// Deploy-time key allowed to call `initialize_admin`, the live admin is in `AdminState`
 pub const ADMIN: Pubkey = pubkey!("CtvobNLW2eYHyme9QYV6peFzBhxrqKjf5LC1pFQRKvKB");
 
 #[program]
 pub mod veerbal_cpmm {
     use super::*;
 
+    pub fn initialize_admin(ctx: Context<InitializeAdmin>) -> Result<()> {
+        instructions::initialize_admin(ctx)
+    }
+
+    pub fn propose_admin(ctx: Context<ProposeAdmin>, new_admin: Pubkey) -> Result<()> {
+        instructions::propose_admin(ctx, new_admin)
+    }
+
+    pub fn accept_admin(ctx: Context<AcceptAdmin>) -> Result<()> {
+        instructions::accept_admin(ctx)
+    }
+
+    pub fn update_role(
+        ctx: Context<UpdateRole>,
+        role: Role,
+        member: Pubkey,
+        granted: bool,
+    ) -> Result<()> {
+        instructions::update_role(ctx, role, member, granted)
+    }
+
     pub fn create_config(
         ctx: Context<CreateAmmConfig>,
         index: u16,
@@ -36,68 +59,239 @@ pub mod veerbal_cpmm {
         Ok(())
     }
 
-    pub fn create_pool(
-        ctx: Context<CreatePool>,
+    pub fn update_amm_config(
+        ctx: Context<UpdateAmmConfig>,
+        params: UpdateAmmConfigParams,
+    ) -> Result<()> {
+        instructions::update_amm_config(ctx, params)
+    }
+
+    pub fn create_pool<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CreatePool<'info>>,
         index: u16,
         init_amount_0: u64,
         init_amount_1: u64,
         open_time: u64,
+        creator_fee_on: CreatorFeeOn,
     ) -> Result<()> {
-        instructions::create_pool(ctx, index, init_amount_0, init_amount_1, open_time)?;
+        instructions::create_pool(
+            ctx,
+            index,
+            init_amount_0,
+            init_amount_1,
+            open_time,
+            creator_fee_on,
+        )?;
         Ok(())
     }
 
-    pub fn deposit(
-        ctx: Context<Deposit>,
+    pub fn update_pool_status(ctx: Context<UpdatePoolStatus>, status: u8) -> Result<()> {
+        instructions::update_pool_status(ctx, status)
+    }
+
+    pub fn update_config_status(ctx: Context<UpdateConfigStatus>, status: u8) -> Result<()> {
+        instructions::update_config_status(ctx, status)
+    }
+
+    pub fn update_global_status(ctx: Context<UpdateGlobalStatus>, status: u8) -> Result<()> {
+        instructions::update_global_status(ctx, status)
+    }
+
+    pub fn update_pool_fees(
+        ctx: Context<UpdatePoolFees>,
+        params: UpdatePoolFeesParams,
+    ) -> Result<()> {
+        instructions::update_pool_fees(ctx, params)
+    }
+
+    pub fn propose_pool_creator(
+        ctx: Context<UpdatePoolCreator>,
+        new_creator: Pubkey,
+    ) -> Result<()> {
+        instructions::propose_pool_creator(ctx, new_creator)
+    }
+
+    pub fn accept_pool_creator(ctx: Context<AcceptPoolCreator>) -> Result<()> {
+        instructions::accept_pool_creator(ctx)
+    }
+
+    pub fn renounce_pool_creator(ctx: Context<UpdatePoolCreator>) -> Result<()> {
+        instructions::renounce_pool_creator(ctx)
+    }
+
+    pub fn update_mint_allowlist(
+        ctx: Context<UpdateMintAllowlist>,
+        mint: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        instructions::update_mint_allowlist(ctx, mint, allowed)
+    }
+
+    pub fn update_transfer_hook_allowlist(
+        ctx: Context<UpdateMintAllowlist>,
+        program_id: Pubkey,
+        allowed: bool,
+    ) -> Result<()> {
+        instructions::update_transfer_hook_allowlist(ctx, program_id, allowed)
+    }
+
+    pub fn deposit<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
         lp_amount: u64,
         maximum_token_0_amount: u64,
         maximum_token_1_amount: u64,
+        native_sol: bool,
     ) -> Result<()> {
         instructions::deposit(
             ctx,
             lp_amount,
             maximum_token_0_amount,
             maximum_token_1_amount,
+            native_sol,
         )?;
         Ok(())
     }
 
-    pub fn withdraw(
-        ctx: Context<Withdraw>,
+    pub fn deposit_base_input<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Deposit<'info>>,
+        is_token_0: bool,
+        amount: u64,
+        maximum_other_amount: u64,
+        minimum_lp_amount: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::deposit_base_input(
+            ctx,
+            is_token_0,
+            amount,
+            maximum_other_amount,
+            minimum_lp_amount,
+            native_sol,
+        )
+    }
+
+    pub fn deposit_single_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, DepositSingleToken<'info>>,
+        amount_in: u64,
+        minimum_lp_amount: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::deposit_single_token(ctx, amount_in, minimum_lp_amount, native_sol)
+    }
+
+    pub fn withdraw<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Withdraw<'info>>,
         lp_amount: u64,
         minimum_token_0_amount: u64,
         minimum_token_1_amount: u64,
+        native_sol: bool,
     ) -> Result<()> {
         instructions::withdraw(
             ctx,
             lp_amount,
             minimum_token_0_amount,
             minimum_token_1_amount,
+            native_sol,
         )?;
         Ok(())
     }
 
-    pub fn swap(ctx: Context<Swap>, amount_in: u64, minimum_amount_out: u64) -> Result<()> {
-        instructions::swap(ctx, amount_in, minimum_amount_out)?;
+    pub fn withdraw_single_token<'info>(
+        ctx: Context<'_, '_, 'info, 'info, WithdrawSingleToken<'info>>,
+        lp_amount: u64,
+        minimum_amount_out: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::withdraw_single_token(ctx, lp_amount, minimum_amount_out, native_sol)
+    }
+
+    pub fn swap<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Swap<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::swap(ctx, amount_in, minimum_amount_out, native_sol)?;
         Ok(())
     }
 
-    pub fn swap_base_output(
-        ctx: Context<SwapBaseOutput>,
+    pub fn swap_base_output<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapBaseOutput<'info>>,
         amount_out: u64,
         maximum_amount_in: u64,
+        native_sol: bool,
     ) -> Result<()> {
-        instructions::swap_base_output(ctx, amount_out, maximum_amount_in)?;
+        instructions::swap_base_output(ctx, amount_out, maximum_amount_in, native_sol)?;
         Ok(())
     }
 
-    pub fn collect_creator_fee(ctx: Context<CollectCreatorFee>) -> Result<()> {
+    pub fn swap_route<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount_in: u64,
+        minimum_amount_out: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::swap_route(ctx, amount_in, minimum_amount_out, native_sol)
+    }
+
+    pub fn swap_route_base_output<'info>(
+        ctx: Context<'_, '_, 'info, 'info, SwapRoute<'info>>,
+        amount_out: u64,
+        maximum_amount_in: u64,
+        native_sol: bool,
+    ) -> Result<()> {
+        instructions::swap_route_base_output(ctx, amount_out, maximum_amount_in, native_sol)
+    }
+
+    pub fn observe(ctx: Context<Observe>, seconds_ago: u64) -> Result<TwapPrice> {
+        instructions::observe(ctx, seconds_ago)
+    }
+
+    pub fn close_pool<'info>(ctx: Context<'_, '_, 'info, 'info, ClosePool<'info>>) -> Result<()> {
+        instructions::close_pool(ctx)
+    }
+
+    pub fn lock_liquidity(ctx: Context<LockLiquidity>, lp_amount: u64) -> Result<()> {
+        instructions::lock_liquidity(ctx, lp_amount)
+    }
+
+    pub fn collect_locked_fee<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectLockedFee<'info>>,
+    ) -> Result<()> {
+        instructions::collect_locked_fee(ctx)
+    }
+
+    pub fn lock_lp_vesting(
+        ctx: Context<LockLpVesting>,
+        nonce: u64,
+        lp_amount: u64,
+        cliff_time: u64,
+        end_time: u64,
+    ) -> Result<()> {
+        instructions::lock_lp_vesting(ctx, nonce, lp_amount, cliff_time, end_time)
+    }
+
+    pub fn claim_vested_lp(ctx: Context<ClaimVestedLp>) -> Result<()> {
+        instructions::claim_vested_lp(ctx)
+    }
+
+    pub fn close_lp_vesting(ctx: Context<CloseLpVesting>) -> Result<()> {
+        instructions::close_lp_vesting(ctx)
+    }
+
+    pub fn collect_creator_fee<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectCreatorFee<'info>>,
+    ) -> Result<()> {
         instructions::collect_creator_fee(ctx)
     }
-    pub fn collect_protocol_fee(ctx: Context<CollectProtocolFee>) -> Result<()> {
+    pub fn collect_protocol_fee<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectProtocolFee<'info>>,
+    ) -> Result<()> {
         instructions::collect_protocol_fee(ctx)
     }
-    pub fn collect_fund_fee(ctx: Context<CollectFundFee>) -> Result<()> {
+    pub fn collect_fund_fee<'info>(
+        ctx: Context<'_, '_, 'info, 'info, CollectFundFee<'info>>,
+    ) -> Result<()> {
         instructions::collect_fund_fee(ctx)
     }
 }
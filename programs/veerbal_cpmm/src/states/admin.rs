@@ -0,0 +1,27 @@
+use anchor_lang::prelude::*;
+
+use crate::states::PoolStatusBitIndex;
+
+/// Singleton holding the program admin, so the key can rotate without a redeploy
+#[account]
+#[derive(InitSpace)]
+pub struct AdminState {
+    pub bump: u8,
+
+    pub admin: Pubkey,
+
+    /// Default pubkey when no transfer is pending
+    pub pending_admin: Pubkey,
+
+    /// Global `PoolStatusBitIndex` bits, checked on top of every pool's own status
+    pub status: u8,
+}
+
+impl AdminState {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    pub fn is_enabled(&self, action: PoolStatusBitIndex) -> bool {
+        let mask = 1u8 << (action as u8);
+        self.status & mask == 0
+    }
+}
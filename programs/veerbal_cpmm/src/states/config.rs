@@ -1,5 +1,7 @@
 use anchor_lang::prelude::*;
 
+use crate::{curve::FEE_RATE_DENOMINATOR, error::ErrorCode, states::PoolStatusBitIndex};
+
 #[account]
 #[derive(InitSpace)]
 pub struct AmmConfig {
@@ -16,8 +18,64 @@ pub struct AmmConfig {
     pub protocol_owner: Pubkey,
     pub fund_owner: Pubkey,
     pub disable_create_pool: bool,
+
+    // Dynamic fee: trade_fee_rate is the base the fee decays back to
+    pub dynamic_fee_enabled: bool,
+    pub min_trade_fee_rate: u64,
+    pub max_trade_fee_rate: u64,
+    /// Seconds for accumulated volatility to decay back to zero
+    pub volatility_decay_period: u64,
+    /// Share of the accumulated price movement added to the fee, in ppm
+    pub volatility_fee_factor: u64,
+
+    /// `PoolStatusBitIndex` bits, checked on top of each pool's own status
+    pub status: u8,
 }
 
 impl AmmConfig {
     pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    pub fn is_enabled(&self, action: PoolStatusBitIndex) -> bool {
+        let mask = 1u8 << (action as u8);
+        self.status & mask == 0
+    }
+
+    /// Bound checks shared by config creation and updates
+    pub fn check_fee_rates(&self) -> Result<()> {
+        let total_trade_fee_rate = self
+            .trade_fee_rate
+            .checked_add(self.creator_fee_rate)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            total_trade_fee_rate < FEE_RATE_DENOMINATOR,
+            ErrorCode::FeeExceedHundredPercentage
+        );
+
+        let total_split_fee_rate = self
+            .protocol_fee_rate
+            .checked_add(self.fund_fee_rate)
+            .ok_or(ErrorCode::MathOverflow)?;
+        require!(
+            total_split_fee_rate < FEE_RATE_DENOMINATOR,
+            ErrorCode::FeeExceedHundredPercentage
+        );
+
+        if self.dynamic_fee_enabled {
+            require!(
+                self.min_trade_fee_rate <= self.trade_fee_rate
+                    && self.trade_fee_rate <= self.max_trade_fee_rate
+                    && self.volatility_decay_period > 0,
+                ErrorCode::InvalidDynamicFee
+            );
+            let max_total_trade_fee_rate = self
+                .max_trade_fee_rate
+                .checked_add(self.creator_fee_rate)
+                .ok_or(ErrorCode::MathOverflow)?;
+            require!(
+                max_total_trade_fee_rate < FEE_RATE_DENOMINATOR,
+                ErrorCode::FeeExceedHundredPercentage
+            );
+        }
+        Ok(())
+    }
 }
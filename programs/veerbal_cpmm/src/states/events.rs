@@ -0,0 +1,232 @@
+use anchor_lang::prelude::*;
+
+use crate::states::{CreatorFeeOn, Role};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum LpChangeType {
+    Deposit,
+    Withdraw,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum FeeType {
+    Protocol,
+    Fund,
+    Creator,
+    LockedLiquidity,
+}
+
+#[event]
+pub struct AdminUpdatedEvent {
+    pub admin: Pubkey,
+    pub pending_admin: Pubkey,
+}
+
+#[event]
+pub struct RoleUpdatedEvent {
+    pub role: Role,
+    pub member: Pubkey,
+    pub granted: bool,
+}
+
+#[event]
+pub struct ConfigCreatedEvent {
+    pub amm_config: Pubkey,
+    pub index: u16,
+    pub trade_fee_rate: u64,
+    pub creator_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+}
+
+#[event]
+pub struct ConfigUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub trade_fee_rate: u64,
+    pub creator_fee_rate: u64,
+    pub protocol_fee_rate: u64,
+    pub fund_fee_rate: u64,
+    pub create_pool_fee: u64,
+    pub protocol_owner: Pubkey,
+    pub fund_owner: Pubkey,
+    pub disable_create_pool: bool,
+    pub dynamic_fee_enabled: bool,
+    pub min_trade_fee_rate: u64,
+    pub max_trade_fee_rate: u64,
+    pub volatility_decay_period: u64,
+    pub volatility_fee_factor: u64,
+}
+
+#[event]
+pub struct PoolCreatedEvent {
+    pub pool_id: Pubkey,
+    pub amm_config: Pubkey,
+    pub pool_creator: Pubkey,
+    pub token_0_mint: Pubkey,
+    pub token_1_mint: Pubkey,
+    pub token_0_vault: Pubkey,
+    pub token_1_vault: Pubkey,
+    pub lp_mint: Pubkey,
+    /// Amounts received by the vaults, after transfer fees
+    pub reserve_0: u64,
+    pub reserve_1: u64,
+    pub lp_supply: u64,
+    pub open_time: u64,
+    pub creator_fee_on: CreatorFeeOn,
+}
+
+#[event]
+pub struct PoolClosedEvent {
+    pub pool_id: Pubkey,
+    pub closed_by: Pubkey,
+    /// Dust swept from the vaults to the protocol owner
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
+
+#[event]
+pub struct PoolStatusUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct ConfigStatusUpdatedEvent {
+    pub amm_config: Pubkey,
+    pub old_status: u8,
+    pub new_status: u8,
+    pub updated_by: Pubkey,
+}
+
+#[event]
+pub struct GlobalStatusUpdatedEvent {
+    pub old_status: u8,
+    pub new_status: u8,
+    pub updated_by: Pubkey,
+}
+
+/// Overrides now in force, None means the AmmConfig rate applies
+#[event]
+pub struct PoolFeesUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub trade_fee_rate: Option<u64>,
+    pub protocol_fee_rate: Option<u64>,
+    pub fund_fee_rate: Option<u64>,
+    pub creator_fee_rate: Option<u64>,
+}
+
+#[event]
+pub struct PoolCreatorUpdatedEvent {
+    pub pool_id: Pubkey,
+    pub pool_creator: Pubkey,
+    pub pending_pool_creator: Pubkey,
+    /// False once renounced, creator fees then stay with LPs
+    pub creator_fee_active: bool,
+}
+
+#[event]
+pub struct MintAllowlistUpdatedEvent {
+    /// A mint, or a transfer hook program id
+    pub key: Pubkey,
+    pub is_transfer_hook_program: bool,
+    pub allowed: bool,
+}
+
+/// Reserves exclude accumulated protocol/fund/creator fees
+#[event]
+pub struct SwapEvent {
+    pub pool_id: Pubkey,
+    pub user: Pubkey,
+    pub is_token_0_input: bool,
+    pub base_input: bool,
+    /// Sent by the user, including the input transfer fee
+    pub amount_in: u64,
+    /// Received by the user, after the output transfer fee
+    pub amount_out: u64,
+    pub input_transfer_fee: u64,
+    pub output_transfer_fee: u64,
+    /// Effective rate, including any dynamic component
+    pub trade_fee_rate: u64,
+    pub trade_fee: u64,
+    pub protocol_fee: u64,
+    pub fund_fee: u64,
+    /// Charged in the output token when not on the input
+    pub is_creator_fee_on_input: bool,
+    pub creator_fee: u64,
+    pub reserve_0_before: u64,
+    pub reserve_1_before: u64,
+    pub reserve_0_after: u64,
+    pub reserve_1_after: u64,
+    pub lp_supply: u64,
+}
+
+/// Reserves exclude accumulated protocol/fund/creator fees
+#[event]
+pub struct LpChangeEvent {
+    pub pool_id: Pubkey,
+    pub user: Pubkey,
+    pub change_type: LpChangeType,
+    pub lp_amount: u64,
+    /// Amounts entering or leaving the vaults, excluding transfer fees
+    pub token_0_amount: u64,
+    pub token_1_amount: u64,
+    pub token_0_transfer_fee: u64,
+    pub token_1_transfer_fee: u64,
+    pub reserve_0_before: u64,
+    pub reserve_1_before: u64,
+    pub reserve_0_after: u64,
+    pub reserve_1_after: u64,
+    pub lp_supply: u64,
+}
+
+#[event]
+pub struct LiquidityLockedEvent {
+    pub pool_id: Pubkey,
+    pub owner: Pubkey,
+    pub lp_amount: u64,
+    /// LP now held for the position, after any earlier locks and claims
+    pub position_lp_amount: u64,
+}
+
+#[event]
+pub struct LpVestingCreatedEvent {
+    pub pool_id: Pubkey,
+    pub lp_vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub funder: Pubkey,
+    pub lp_amount: u64,
+    pub start_time: u64,
+    pub cliff_time: u64,
+    pub end_time: u64,
+}
+
+#[event]
+pub struct VestedLpClaimedEvent {
+    pub pool_id: Pubkey,
+    pub lp_vesting: Pubkey,
+    pub beneficiary: Pubkey,
+    pub lp_amount: u64,
+    /// Total claimed so far, including this claim
+    pub claimed_amount: u64,
+}
+
+#[event]
+pub struct LpVestingClosedEvent {
+    pub pool_id: Pubkey,
+    pub lp_vesting: Pubkey,
+    pub closed_by: Pubkey,
+}
+
+#[event]
+pub struct FeeCollectedEvent {
+    pub pool_id: Pubkey,
+    pub fee_type: FeeType,
+    pub receiver: Pubkey,
+    pub amount_0: u64,
+    pub amount_1: u64,
+}
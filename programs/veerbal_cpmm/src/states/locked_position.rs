@@ -0,0 +1,23 @@
+use anchor_lang::prelude::*;
+
+/// LP locked for good by `lock_liquidity`. The owner can only claim the
+/// fees that grow on top of `locked_liquidity`, never the principal
+#[account]
+#[derive(InitSpace)]
+pub struct LockedPosition {
+    pub bump: u8,
+
+    pub pool_id: Pubkey,
+
+    pub owner: Pubkey,
+
+    /// LP held in the pool's locked LP vault, shrinks as fees are claimed
+    pub lp_amount: u64,
+
+    /// sqrt(reserve_0 * reserve_1) share that can never leave the pool
+    pub locked_liquidity: u128,
+}
+
+impl LockedPosition {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+}
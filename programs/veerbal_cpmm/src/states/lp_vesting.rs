@@ -0,0 +1,55 @@
+use anchor_lang::prelude::*;
+
+/// LP escrowed by `lock_lp_vesting` for one beneficiary of a pool, keyed by
+/// the funder and a funder-chosen nonce. Nothing unlocks before `cliff_time`,
+/// then it vests linearly from `start_time` to `end_time`. A cliff-only
+/// schedule has `cliff_time == end_time`
+#[account]
+#[derive(InitSpace)]
+pub struct LpVesting {
+    pub bump: u8,
+
+    pub pool_id: Pubkey,
+
+    pub beneficiary: Pubkey,
+
+    /// Paid for the escrow, gets the rent back when it is closed
+    pub funder: Pubkey,
+
+    pub nonce: u64,
+
+    pub total_amount: u64,
+
+    pub claimed_amount: u64,
+
+    /// Lock time, or the pool's `open_time` if the pool isn't open yet
+    pub start_time: u64,
+
+    pub cliff_time: u64,
+
+    pub end_time: u64,
+}
+
+impl LpVesting {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    /// LP vested by `block_timestamp`, claimed or not
+    pub fn vested_amount(&self, block_timestamp: u64) -> Option<u64> {
+        if block_timestamp < self.cliff_time {
+            return Some(0);
+        }
+        if block_timestamp >= self.end_time {
+            return Some(self.total_amount);
+        }
+        // cliff_time <= block_timestamp < end_time, so end_time > start_time
+        let vested = (self.total_amount as u128)
+            .checked_mul((block_timestamp - self.start_time) as u128)?
+            .checked_div((self.end_time - self.start_time) as u128)?;
+        u64::try_from(vested).ok()
+    }
+
+    pub fn claimable_amount(&self, block_timestamp: u64) -> Option<u64> {
+        self.vested_amount(block_timestamp)?
+            .checked_sub(self.claimed_amount)
+    }
+}
@@ -0,0 +1,31 @@
+use anchor_lang::prelude::*;
+
+pub const MAX_ALLOWLISTED_MINTS: usize = 64;
+pub const MAX_ALLOWLISTED_HOOK_PROGRAMS: usize = 16;
+
+/// Admin-managed exceptions to the mint extension checks in `create_pool`
+#[account]
+#[derive(InitSpace)]
+pub struct MintAllowlist {
+    pub bump: u8,
+
+    /// Mints trusted regardless of their extensions
+    #[max_len(MAX_ALLOWLISTED_MINTS)]
+    pub mints: Vec<Pubkey>,
+
+    /// Transfer hook programs a mint may point to
+    #[max_len(MAX_ALLOWLISTED_HOOK_PROGRAMS)]
+    pub transfer_hook_programs: Vec<Pubkey>,
+}
+
+impl MintAllowlist {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    pub fn is_mint_allowed(&self, mint: &Pubkey) -> bool {
+        self.mints.contains(mint)
+    }
+
+    pub fn is_transfer_hook_allowed(&self, program_id: &Pubkey) -> bool {
+        self.transfer_hook_programs.contains(program_id)
+    }
+}
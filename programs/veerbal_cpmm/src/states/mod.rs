@@ -1,5 +1,26 @@
 pub mod config;
 pub use config::*;
 
+pub mod admin;
+pub use admin::*;
+
+pub mod role_registry;
+pub use role_registry::*;
+
 pub mod pool;
 pub use pool::*;
+
+pub mod oracle;
+pub use oracle::*;
+
+pub mod locked_position;
+pub use locked_position::*;
+
+pub mod lp_vesting;
+pub use lp_vesting::*;
+
+pub mod mint_allowlist;
+pub use mint_allowlist::*;
+
+pub mod events;
+pub use events::*;
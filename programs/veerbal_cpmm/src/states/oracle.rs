@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+
+/// Number of observations kept in the pool's ring buffer
+pub const OBSERVATION_NUM: usize = 32;
+/// Minimum seconds between two observations. With `OBSERVATION_NUM` this
+/// gives `observe` about 8 hours of history when the pool trades at least
+/// every 15 minutes, a window may start up to one spacing earlier than asked
+pub const OBSERVATION_UPDATE_DURATION: u64 = 15 * 60;
+/// Prices are Q32.32 fixed point
+pub const PRICE_FRACTIONAL_BITS: u32 = 32;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, InitSpace)]
+pub struct Observation {
+    pub block_timestamp: u64,
+    pub cumulative_token_0_price_x32: u128,
+    pub cumulative_token_1_price_x32: u128,
+}
+
+/// Return value of the `observe` view
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy)]
+pub struct TwapPrice {
+    /// token0 priced in token1
+    pub token_0_price_x32: u128,
+    /// token1 priced in token0
+    pub token_1_price_x32: u128,
+    /// Seconds actually covered, at least the requested window
+    pub window: u64,
+}
+
+/// Spot prices of token0 and token1 in Q32.32, None while a side is empty
+pub fn spot_prices_x32(vault_0_amount: u64, vault_1_amount: u64) -> Option<(u128, u128)> {
+    if vault_0_amount == 0 || vault_1_amount == 0 {
+        return None;
+    }
+    let price_0 = ((vault_1_amount as u128) << PRICE_FRACTIONAL_BITS) / vault_0_amount as u128;
+    let price_1 = ((vault_0_amount as u128) << PRICE_FRACTIONAL_BITS) / vault_1_amount as u128;
+    Some((price_0, price_1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::states::PoolState;
+
+    fn pool_at(block_timestamp: u64) -> PoolState {
+        let mut pool_state =
+            PoolState::deserialize(&mut &vec![0u8; PoolState::LEN - 8][..]).unwrap();
+        pool_state.init_oracle(100, 400, block_timestamp);
+        pool_state
+    }
+
+    #[test]
+    fn test_spot_prices_x32() {
+        // 100 token0 against 400 token1: token0 is worth 4, token1 0.25
+        assert_eq!(spot_prices_x32(100, 400), Some((4 << 32, 1 << 30)));
+        assert_eq!(spot_prices_x32(0, 400), None);
+        assert_eq!(spot_prices_x32(100, 0), None);
+    }
+
+    #[test]
+    fn test_cumulative_prices() {
+        let mut pool_state = pool_at(1000);
+
+        // 10s at price 4, then 30s at price 0.25
+        pool_state.update_oracle(400, 100, 1010);
+        assert_eq!(pool_state.cumulative_token_0_price_x32, 10 * (4 << 32));
+        pool_state.update_oracle(100, 100, 1040);
+        assert_eq!(
+            pool_state.cumulative_token_0_price_x32,
+            10 * (4 << 32) + 30 * (1 << 30)
+        );
+        assert_eq!(
+            pool_state.cumulative_token_1_price_x32,
+            10 * (1 << 30) + 30 * (4 << 32)
+        );
+
+        // A second update in the same second adds nothing
+        pool_state.update_oracle(1, 1_000_000, 1040);
+        assert_eq!(
+            pool_state.cumulative_token_0_price_x32,
+            10 * (4 << 32) + 30 * (1 << 30)
+        );
+    }
+
+    #[test]
+    fn test_elapsed_time_priced_at_stored_reserves() {
+        let mut pool_state = pool_at(1000);
+
+        // Reserves skewed within the instruction only price the time after it
+        pool_state.update_oracle(1, 1_000_000, 1100);
+        assert_eq!(pool_state.cumulative_token_0_price_x32, 100 * (4 << 32));
+        assert_eq!(pool_state.cumulative_token_1_price_x32, 100 * (1 << 30));
+
+        // and are undone before any time passes at them
+        pool_state.update_oracle(100, 400, 1100);
+        assert_eq!(
+            pool_state.cumulative_prices_at(1200),
+            (200 * (4 << 32), 200 * (1 << 30))
+        );
+    }
+
+    #[test]
+    fn test_observation_spacing() {
+        let mut pool_state = pool_at(1000);
+        let spacing = OBSERVATION_UPDATE_DURATION;
+
+        // Under one spacing since the last observation only moves the accumulators
+        pool_state.update_oracle(100, 100, 1000 + spacing - 1);
+        assert_eq!(pool_state.observation_index, 0);
+
+        pool_state.update_oracle(100, 100, 1000 + spacing);
+        assert_eq!(pool_state.observation_index, 1);
+        assert_eq!(pool_state.observations[1].block_timestamp, 1000 + spacing);
+        assert_eq!(
+            pool_state.observations[1].cumulative_token_0_price_x32,
+            (spacing as u128 - 1) * (4 << 32) + (1 << 32)
+        );
+
+        pool_state.update_oracle(100, 100, 1000 + 2 * spacing - 1);
+        assert_eq!(pool_state.observation_index, 1);
+        pool_state.update_oracle(100, 100, 1000 + 2 * spacing);
+        assert_eq!(pool_state.observation_index, 2);
+    }
+
+    #[test]
+    fn test_observation_ring_buffer_wraps() {
+        let mut pool_state = pool_at(1000);
+        let spacing = OBSERVATION_UPDATE_DURATION;
+
+        for step in 1..=OBSERVATION_NUM as u64 + 1 {
+            pool_state.update_oracle(100, 100, 1000 + step * spacing);
+        }
+
+        // 33 observations written after the initial one: slots 0 and 1 were reused
+        assert_eq!(pool_state.observation_index, 1);
+        assert_eq!(
+            pool_state.observations[0].block_timestamp,
+            1000 + OBSERVATION_NUM as u64 * spacing
+        );
+        assert_eq!(
+            pool_state.observations[1].block_timestamp,
+            1000 + (OBSERVATION_NUM as u64 + 1) * spacing
+        );
+        let oldest = pool_state
+            .observations
+            .iter()
+            .map(|observation| observation.block_timestamp)
+            .min()
+            .unwrap();
+        assert_eq!(oldest, 1000 + 2 * spacing);
+    }
+}
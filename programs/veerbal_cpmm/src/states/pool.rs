@@ -1,6 +1,12 @@
 use anchor_lang::prelude::*;
 
-use crate::error::ErrorCode;
+use crate::{
+    curve::{decayed_volatility, dynamic_fee_rate, price_change_rate},
+    error::ErrorCode,
+    states::{
+        spot_prices_x32, AmmConfig, Observation, OBSERVATION_NUM, OBSERVATION_UPDATE_DURATION,
+    },
+};
 
 pub enum PoolStatusBitIndex {
     Deposit,
@@ -8,6 +14,20 @@ pub enum PoolStatusBitIndex {
     Swap,
 }
 
+/// Which token a pool's creator fee is charged in, stored as `creator_fee_on`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum CreatorFeeOn {
+    /// Whichever token is the swap input
+    BothToken,
+    OnlyToken0,
+    OnlyToken1,
+}
+
+/// Every bit defined by `PoolStatusBitIndex`
+pub const POOL_STATUS_MASK: u8 = (1 << PoolStatusBitIndex::Deposit as u8)
+    | (1 << PoolStatusBitIndex::Withdraw as u8)
+    | (1 << PoolStatusBitIndex::Swap as u8);
+
 #[account]
 #[derive(InitSpace)]
 pub struct PoolState {
@@ -61,13 +81,36 @@ pub struct PoolState {
     pub mint_0_decimals: u8,
     pub mint_1_decimals: u8,
     pub lp_mint_decimals: u8,
+
+    // TWAP oracle, accumulators wrap on overflow
+    pub cumulative_token_0_price_x32: u128,
+    pub cumulative_token_1_price_x32: u128,
+    pub oracle_timestamp: u64,
+    // Clean reserves as they stood after the last instruction, priced until the next one
+    pub oracle_reserve_0: u64,
+    pub oracle_reserve_1: u64,
+    pub observation_index: u16,
+    pub observations: [Observation; OBSERVATION_NUM],
+
+    // Dynamic fee, price movement in ppm decaying since volatility_timestamp
+    pub volatility_accumulator: u64,
+    pub volatility_timestamp: u64,
+
+    // Per-pool fee rates set by the admin, None falls back to the AmmConfig
+    pub trade_fee_rate_override: Option<u64>,
+    pub protocol_fee_rate_override: Option<u64>,
+    pub fund_fee_rate_override: Option<u64>,
+    pub creator_fee_rate_override: Option<u64>,
+
+    // Two-step creator transfer, default pubkey when nothing is pending
+    pub pending_pool_creator: Pubkey,
 }
 
 impl PoolState {
     pub const LEN: usize = 8 + Self::INIT_SPACE;
 
     pub fn is_enabled(&self, action: PoolStatusBitIndex) -> bool {
-        let mask = (1 as u8) << (action as u8);
+        let mask = 1u8 << (action as u8);
         self.status & mask == 0
     }
 
@@ -98,4 +141,215 @@ impl PoolState {
                 .ok_or(ErrorCode::MathOverflow)?,
         ))
     }
+
+    /// Whether a swap in this direction charges the creator fee on its input
+    pub fn is_creator_fee_on_input(&self, is_token_0_input: bool) -> bool {
+        if self.creator_fee_on == CreatorFeeOn::OnlyToken0 as u8 {
+            is_token_0_input
+        } else if self.creator_fee_on == CreatorFeeOn::OnlyToken1 as u8 {
+            !is_token_0_input
+        } else {
+            true
+        }
+    }
+
+    /// Creator fee rates charged on the (input, output) of a swap in this direction
+    pub fn creator_fee_rates(&self, amm_config: &AmmConfig, is_token_0_input: bool) -> (u64, u64) {
+        let rate = self.creator_fee_rate(amm_config);
+        if self.is_creator_fee_on_input(is_token_0_input) {
+            (rate, 0)
+        } else {
+            (0, rate)
+        }
+    }
+
+    /// Creator fees booked for the creator out of those charged, a renounced
+    /// pool leaves them in the reserves for LPs
+    pub fn booked_creator_fees(&self, creator_fee_in: u128, creator_fee_out: u128) -> (u128, u128) {
+        if self.creator_fee_active {
+            (creator_fee_in, creator_fee_out)
+        } else {
+            (0, 0)
+        }
+    }
+
+    /// Book the protocol/fund/creator shares of a trade fee charged in one token
+    pub fn accrue_fees(
+        &mut self,
+        is_token_0: bool,
+        protocol_fee: u64,
+        fund_fee: u64,
+        creator_fee: u64,
+    ) -> Result<()> {
+        let (protocol, fund, creator) = if is_token_0 {
+            (
+                &mut self.protocol_token_0_fee,
+                &mut self.fund_token_0_fee,
+                &mut self.creator_token_0_fee,
+            )
+        } else {
+            (
+                &mut self.protocol_token_1_fee,
+                &mut self.fund_token_1_fee,
+                &mut self.creator_token_1_fee,
+            )
+        };
+        *protocol = protocol
+            .checked_add(protocol_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        *fund = fund.checked_add(fund_fee).ok_or(ErrorCode::MathOverflow)?;
+        *creator = creator
+            .checked_add(creator_fee)
+            .ok_or(ErrorCode::MathOverflow)?;
+        Ok(())
+    }
+
+    pub fn init_oracle(&mut self, reserve_0: u64, reserve_1: u64, block_timestamp: u64) {
+        self.cumulative_token_0_price_x32 = 0;
+        self.cumulative_token_1_price_x32 = 0;
+        self.oracle_timestamp = block_timestamp;
+        self.oracle_reserve_0 = reserve_0;
+        self.oracle_reserve_1 = reserve_1;
+        self.observation_index = 0;
+        self.observations = [Observation::default(); OBSERVATION_NUM];
+        self.observations[0].block_timestamp = block_timestamp;
+    }
+
+    /// Cumulative prices at `block_timestamp`, extending the last update with
+    /// the reserves stored then
+    pub fn cumulative_prices_at(&self, block_timestamp: u64) -> (u128, u128) {
+        let time_elapsed = block_timestamp.saturating_sub(self.oracle_timestamp) as u128;
+        match spot_prices_x32(self.oracle_reserve_0, self.oracle_reserve_1) {
+            Some((price_0, price_1)) if time_elapsed > 0 => (
+                self.cumulative_token_0_price_x32
+                    .wrapping_add(price_0.wrapping_mul(time_elapsed)),
+                self.cumulative_token_1_price_x32
+                    .wrapping_add(price_1.wrapping_mul(time_elapsed)),
+            ),
+            _ => (
+                self.cumulative_token_0_price_x32,
+                self.cumulative_token_1_price_x32,
+            ),
+        }
+    }
+
+    /// Accumulate prices since the last update at the reserves stored then,
+    /// and store the clean reserves left by this instruction. Called once the
+    /// instruction's reserves are final, so balances moved within the
+    /// transaction never price time that already elapsed
+    pub fn update_oracle(&mut self, reserve_0: u64, reserve_1: u64, block_timestamp: u64) {
+        if block_timestamp > self.oracle_timestamp {
+            let (cumulative_0, cumulative_1) = self.cumulative_prices_at(block_timestamp);
+            self.cumulative_token_0_price_x32 = cumulative_0;
+            self.cumulative_token_1_price_x32 = cumulative_1;
+            self.oracle_timestamp = block_timestamp;
+
+            let last_observation = self.observations[self.observation_index as usize];
+            if block_timestamp
+                >= last_observation
+                    .block_timestamp
+                    .saturating_add(OBSERVATION_UPDATE_DURATION)
+            {
+                let next_index = (self.observation_index as usize + 1) % OBSERVATION_NUM;
+                self.observations[next_index] = Observation {
+                    block_timestamp,
+                    cumulative_token_0_price_x32: cumulative_0,
+                    cumulative_token_1_price_x32: cumulative_1,
+                };
+                self.observation_index = next_index as u16;
+            }
+        }
+        self.oracle_reserve_0 = reserve_0;
+        self.oracle_reserve_1 = reserve_1;
+    }
+
+    /// Trade fee rate for a swap at `block_timestamp`, including the dynamic
+    /// component when the config enables it. An override is a fixed rate.
+    pub fn trade_fee_rate(&self, amm_config: &AmmConfig, block_timestamp: u64) -> Result<u64> {
+        // Overrides were checked against the config as it was when they were
+        // set, a later config change can push the combined rates past 100%
+        if self.has_fee_overrides() {
+            require!(
+                self.check_fee_rates(amm_config).is_ok(),
+                ErrorCode::InvalidPoolFeeRates
+            );
+        }
+        if let Some(rate) = self.trade_fee_rate_override {
+            return Ok(rate);
+        }
+        if !amm_config.dynamic_fee_enabled {
+            return Ok(amm_config.trade_fee_rate);
+        }
+        let volatility = decayed_volatility(
+            self.volatility_accumulator,
+            block_timestamp.saturating_sub(self.volatility_timestamp),
+            amm_config.volatility_decay_period,
+        );
+        let rate = dynamic_fee_rate(
+            amm_config.trade_fee_rate,
+            volatility,
+            amm_config.volatility_fee_factor,
+            amm_config.min_trade_fee_rate,
+            amm_config.max_trade_fee_rate,
+        )
+        .ok_or(ErrorCode::MathOverflow)?;
+        Ok(rate)
+    }
+
+    pub fn protocol_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        self.protocol_fee_rate_override
+            .unwrap_or(amm_config.protocol_fee_rate)
+    }
+
+    pub fn fund_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        self.fund_fee_rate_override
+            .unwrap_or(amm_config.fund_fee_rate)
+    }
+
+    pub fn creator_fee_rate(&self, amm_config: &AmmConfig) -> u64 {
+        self.creator_fee_rate_override
+            .unwrap_or(amm_config.creator_fee_rate)
+    }
+
+    pub fn has_fee_overrides(&self) -> bool {
+        self.trade_fee_rate_override.is_some()
+            || self.protocol_fee_rate_override.is_some()
+            || self.fund_fee_rate_override.is_some()
+            || self.creator_fee_rate_override.is_some()
+    }
+
+    /// Run the config bound checks against the rates this pool actually charges
+    pub fn check_fee_rates(&self, amm_config: &AmmConfig) -> Result<()> {
+        let mut effective = (*amm_config).clone();
+        if let Some(rate) = self.trade_fee_rate_override {
+            effective.trade_fee_rate = rate;
+            effective.dynamic_fee_enabled = false;
+        }
+        effective.protocol_fee_rate = self.protocol_fee_rate(amm_config);
+        effective.fund_fee_rate = self.fund_fee_rate(amm_config);
+        effective.creator_fee_rate = self.creator_fee_rate(amm_config);
+        effective.check_fee_rates()
+    }
+
+    /// Fold the price move of a swap, from pre/post clean reserves, into the
+    /// decayed volatility
+    pub fn update_volatility(
+        &mut self,
+        amm_config: &AmmConfig,
+        reserves_before: (u64, u64),
+        reserves_after: (u64, u64),
+        block_timestamp: u64,
+    ) {
+        if !amm_config.dynamic_fee_enabled {
+            return;
+        }
+        let volatility = decayed_volatility(
+            self.volatility_accumulator,
+            block_timestamp.saturating_sub(self.volatility_timestamp),
+            amm_config.volatility_decay_period,
+        );
+        self.volatility_accumulator =
+            volatility.saturating_add(price_change_rate(reserves_before, reserves_after));
+        self.volatility_timestamp = block_timestamp;
+    }
 }
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::error::ErrorCode;
+
+pub const MAX_ROLE_MEMBERS: usize = 8;
+
+/// Narrow permissions granted by the admin, who keeps every permission
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    /// Flips `PoolStatusBitIndex` bits with `update_pool_status`
+    Pauser,
+    /// Updates fee rates and fee receivers on configs and pools
+    FeeManager,
+    /// Creates new AmmConfigs
+    ConfigCreator,
+}
+
+/// Members of each `Role`, created alongside the `AdminState`
+#[account]
+#[derive(InitSpace)]
+pub struct RoleRegistry {
+    pub bump: u8,
+
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub pausers: Vec<Pubkey>,
+
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub fee_managers: Vec<Pubkey>,
+
+    #[max_len(MAX_ROLE_MEMBERS)]
+    pub config_creators: Vec<Pubkey>,
+}
+
+impl RoleRegistry {
+    pub const LEN: usize = 8 + Self::INIT_SPACE;
+
+    fn members(&self, role: Role) -> &Vec<Pubkey> {
+        match role {
+            Role::Pauser => &self.pausers,
+            Role::FeeManager => &self.fee_managers,
+            Role::ConfigCreator => &self.config_creators,
+        }
+    }
+
+    pub fn has_role(&self, role: Role, key: &Pubkey) -> bool {
+        self.members(role).contains(key)
+    }
+
+    pub fn set_role(&mut self, role: Role, member: Pubkey, granted: bool) -> Result<()> {
+        let members = match role {
+            Role::Pauser => &mut self.pausers,
+            Role::FeeManager => &mut self.fee_managers,
+            Role::ConfigCreator => &mut self.config_creators,
+        };
+        if granted {
+            if !members.contains(&member) {
+                require!(members.len() < MAX_ROLE_MEMBERS, ErrorCode::RoleFull);
+                members.push(member);
+            }
+        } else {
+            members.retain(|entry| *entry != member);
+        }
+        Ok(())
+    }
+}
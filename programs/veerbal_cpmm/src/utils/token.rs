@@ -0,0 +1,312 @@
+use anchor_lang::{prelude::*, system_program};
+use anchor_spl::{
+    token::{spl_token, Token},
+    token_2022::spl_token_2022::{
+        extension::{
+            default_account_state::DefaultAccountState, transfer_fee::TransferFeeConfig,
+            transfer_hook::TransferHook, BaseStateWithExtensions, ExtensionType,
+            StateWithExtensions,
+        },
+        native_mint,
+        onchain::invoke_transfer_checked,
+        state::{AccountState, Mint as SplMint},
+    },
+    token_2022_extensions::{harvest_withheld_tokens_to_mint, HarvestWithheldTokensToMint},
+    token_interface::{self, CloseAccount, Mint, SyncNative, TransferChecked},
+};
+
+use crate::{error::ErrorCode, states::MintAllowlist};
+
+/// User → pool vault, signed by the user. `hook_accounts` carries the hook
+/// program and its extra accounts when the mint has a transfer hook
+pub fn transfer_from_user_to_pool_vault<'info>(
+    authority: AccountInfo<'info>,
+    from: AccountInfo<'info>,
+    to_vault: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if has_transfer_hook(mint)? {
+        invoke_transfer_checked(
+            token_program.key,
+            from,
+            mint.to_account_info(),
+            to_vault,
+            authority,
+            hook_accounts,
+            amount,
+            mint.decimals,
+            &[],
+        )?;
+        return Ok(());
+    }
+    token_interface::transfer_checked(
+        CpiContext::new(
+            token_program,
+            TransferChecked {
+                from,
+                mint: mint.to_account_info(),
+                to: to_vault,
+                authority,
+            },
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Pool vault → user, signed by the authority PDA
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_from_pool_vault_to_user<'info>(
+    authority: AccountInfo<'info>,
+    from_vault: AccountInfo<'info>,
+    to: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: AccountInfo<'info>,
+    amount: u64,
+    signer_seeds: &[&[&[u8]]],
+    hook_accounts: &[AccountInfo<'info>],
+) -> Result<()> {
+    if amount == 0 {
+        return Ok(());
+    }
+    if has_transfer_hook(mint)? {
+        invoke_transfer_checked(
+            token_program.key,
+            from_vault,
+            mint.to_account_info(),
+            to,
+            authority,
+            hook_accounts,
+            amount,
+            mint.decimals,
+            signer_seeds,
+        )?;
+        return Ok(());
+    }
+    token_interface::transfer_checked(
+        CpiContext::new_with_signer(
+            token_program,
+            TransferChecked {
+                from: from_vault,
+                mint: mint.to_account_info(),
+                to,
+                authority,
+            },
+            signer_seeds,
+        ),
+        amount,
+        mint.decimals,
+    )
+}
+
+/// Whether transfers of `mint` invoke a transfer hook program
+fn has_transfer_hook(mint: &InterfaceAccount<Mint>) -> Result<bool> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(false);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+    Ok(mint
+        .get_extension::<TransferHook>()
+        .is_ok_and(|transfer_hook| Option::<Pubkey>::from(transfer_hook.program_id).is_some()))
+}
+
+/// Close an emptied pool vault, signed by the authority PDA. Token-2022
+/// fees withheld in the vault are harvested to the mint first, or the close fails
+pub fn close_pool_vault<'info>(
+    authority: AccountInfo<'info>,
+    vault: AccountInfo<'info>,
+    destination: AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner != Token::id() {
+        let has_transfer_fee = {
+            let mint_data = mint_info.try_borrow_data()?;
+            let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+            mint.get_extension::<TransferFeeConfig>().is_ok()
+        };
+        if has_transfer_fee {
+            harvest_withheld_tokens_to_mint(
+                CpiContext::new(
+                    token_program.clone(),
+                    HarvestWithheldTokensToMint {
+                        token_program_id: token_program.clone(),
+                        mint: mint_info,
+                    },
+                ),
+                vec![vault.clone()],
+            )?;
+        }
+    }
+    token_interface::close_account(CpiContext::new_with_signer(
+        token_program,
+        CloseAccount {
+            account: vault,
+            destination,
+            authority,
+        },
+        signer_seeds,
+    ))
+}
+
+/// Fee withheld by the mint's TransferFee extension when `amount` is sent
+pub fn get_transfer_fee(mint: &InterfaceAccount<Mint>, amount: u64) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+
+    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+        transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(ErrorCode::MathOverflow)?
+    } else {
+        0
+    };
+    Ok(fee)
+}
+
+/// Extra amount to send so that exactly `post_fee_amount` arrives
+pub fn get_transfer_inverse_fee(
+    mint: &InterfaceAccount<Mint>,
+    post_fee_amount: u64,
+) -> Result<u64> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(0);
+    }
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+
+    let fee = if let Ok(transfer_fee_config) = mint.get_extension::<TransferFeeConfig>() {
+        transfer_fee_config
+            .calculate_inverse_epoch_fee(Clock::get()?.epoch, post_fee_amount)
+            .ok_or(ErrorCode::MathOverflow)?
+    } else {
+        0
+    };
+    Ok(fee)
+}
+
+/// Reject mints whose extensions break pool safety, unless the mint is allowlisted
+pub fn check_mint_extensions(
+    mint: &InterfaceAccount<Mint>,
+    mint_allowlist: Option<&MintAllowlist>,
+) -> Result<()> {
+    let mint_info = mint.to_account_info();
+    if *mint_info.owner == Token::id() {
+        return Ok(());
+    }
+    if mint_allowlist.is_some_and(|allowlist| allowlist.is_mint_allowed(&mint.key())) {
+        return Ok(());
+    }
+
+    let mint_data = mint_info.try_borrow_data()?;
+    let mint = StateWithExtensions::<SplMint>::unpack(&mint_data)?;
+
+    for extension in mint.get_extension_types()? {
+        match extension {
+            ExtensionType::TransferFeeConfig
+            | ExtensionType::MetadataPointer
+            | ExtensionType::TokenMetadata
+            | ExtensionType::GroupPointer
+            | ExtensionType::TokenGroup
+            | ExtensionType::GroupMemberPointer
+            | ExtensionType::TokenGroupMember
+            | ExtensionType::InterestBearingConfig
+            | ExtensionType::MintCloseAuthority => {}
+            ExtensionType::PermanentDelegate => {
+                return err!(ErrorCode::PermanentDelegateNotAllowed)
+            }
+            ExtensionType::NonTransferable => return err!(ErrorCode::NonTransferableNotAllowed),
+            ExtensionType::Pausable => return err!(ErrorCode::PausableNotAllowed),
+            ExtensionType::DefaultAccountState => {
+                let default_state = mint.get_extension::<DefaultAccountState>()?;
+                require!(
+                    default_state.state != AccountState::Frozen as u8,
+                    ErrorCode::DefaultAccountStateFrozen
+                );
+            }
+            ExtensionType::TransferHook => {
+                let transfer_hook = mint.get_extension::<TransferHook>()?;
+                if let Some(program_id) = Option::<Pubkey>::from(transfer_hook.program_id) {
+                    require!(
+                        mint_allowlist.is_some_and(
+                            |allowlist| allowlist.is_transfer_hook_allowed(&program_id)
+                        ),
+                        ErrorCode::TransferHookNotAllowed
+                    );
+                }
+            }
+            _ => return err!(ErrorCode::UnsupportedMintExtension),
+        }
+    }
+    Ok(())
+}
+
+pub fn is_native_mint(mint: &Pubkey) -> bool {
+    *mint == spl_token::native_mint::ID || *mint == native_mint::ID
+}
+
+/// Move lamports from the signer into its wSOL token account and sync the balance
+pub fn wrap_native_sol<'info>(
+    signer: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    system_program: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    lamports: u64,
+) -> Result<()> {
+    if lamports == 0 {
+        return Ok(());
+    }
+    system_program::transfer(
+        CpiContext::new(
+            system_program,
+            system_program::Transfer {
+                from: signer,
+                to: token_account.clone(),
+            },
+        ),
+        lamports,
+    )?;
+    token_interface::sync_native(CpiContext::new(
+        token_program,
+        SyncNative {
+            account: token_account,
+        },
+    ))
+}
+
+/// Close the signer's wSOL token account, returning every lamport to the signer.
+/// `balance_before` is the account's balance when the instruction started, an
+/// account that already held wSOL is left open so its balance isn't swept
+pub fn unwrap_native_sol<'info>(
+    signer: AccountInfo<'info>,
+    token_account: AccountInfo<'info>,
+    token_program: AccountInfo<'info>,
+    balance_before: u64,
+) -> Result<()> {
+    if balance_before > 0 {
+        return Ok(());
+    }
+    token_interface::close_account(CpiContext::new(
+        token_program,
+        CloseAccount {
+            account: token_account,
+            destination: signer.clone(),
+            authority: signer,
+        },
+    ))
+}
@@ -0,0 +1,180 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { Keypair, PublicKey, SystemProgram, Transaction } from "@solana/web3.js";
+import { ensureAdminState, get_amm_config_pda } from "./utils";
+import { assert } from "chai";
+
+describe("admin", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 11000) % 65535;
+
+  let adminState: PublicKey;
+
+  const emptyParams = {
+    tradeFeeRate: null,
+    protocolFeeRate: null,
+    fundFeeRate: null,
+    creatorFeeRate: null,
+    createPoolFee: null,
+    protocolOwner: null,
+    fundOwner: null,
+    disableCreatePool: null,
+    dynamicFeeEnabled: null,
+    minTradeFeeRate: null,
+    maxTradeFeeRate: null,
+    volatilityDecayPeriod: null,
+    volatilityFeeFactor: null,
+  };
+
+  before(async () => {
+    adminState = await ensureAdminState(program, owner);
+  });
+
+  it("rejects a proposal from a non-admin", async () => {
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .proposeAdmin(stranger.publicKey)
+        .accounts({ owner: stranger.publicKey, adminState } as any)
+        .signers([stranger])
+        .rpc();
+      assert.fail("Only the admin can propose");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+  });
+
+  it("transfers the admin in two steps", async () => {
+    const newAdmin = Keypair.generate();
+
+    await program.methods
+      .proposeAdmin(newAdmin.publicKey)
+      .accounts({ owner: owner.publicKey, adminState } as any)
+      .signers([owner])
+      .rpc();
+
+    let state = await program.account.adminState.fetch(adminState);
+    assert(state.admin.equals(owner.publicKey), "Admin moves only on accept");
+    assert(state.pendingAdmin.equals(newAdmin.publicKey));
+
+    await program.methods
+      .acceptAdmin()
+      .accounts({ newAdmin: newAdmin.publicKey, adminState } as any)
+      .signers([newAdmin])
+      .rpc();
+
+    state = await program.account.adminState.fetch(adminState);
+    assert(state.admin.equals(newAdmin.publicKey));
+    assert(state.pendingAdmin.equals(PublicKey.default));
+
+    // Hand it back so the other suites keep running as the wallet
+    await program.methods
+      .proposeAdmin(owner.publicKey)
+      .accounts({ owner: newAdmin.publicKey, adminState } as any)
+      .signers([newAdmin])
+      .rpc();
+    await program.methods
+      .acceptAdmin()
+      .accounts({ newAdmin: owner.publicKey, adminState } as any)
+      .signers([owner])
+      .rpc();
+
+    state = await program.account.adminState.fetch(adminState);
+    assert(state.admin.equals(owner.publicKey));
+  });
+
+  it("lets a config creator create configs without owning their fees", async () => {
+    const configCreator = Keypair.generate();
+    // The config creator pays rent for the new config
+    await provider.sendAndConfirm(
+      new Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: owner.publicKey,
+          toPubkey: configCreator.publicKey,
+          lamports: 10_000_000,
+        })
+      ),
+      [owner]
+    );
+
+    await program.methods
+      .updateRole({ configCreator: {} }, configCreator.publicKey, true)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+
+    const configPDA = get_amm_config_pda({ index: configIndex, program_id: program.programId });
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000),
+        new anchor.BN(250000),
+        new anchor.BN(0)
+      )
+      .accounts({ owner: configCreator.publicKey, ammConfig: configPDA } as any)
+      .signers([configCreator])
+      .rpc();
+
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert(config.protocolOwner.equals(owner.publicKey), "Fees go to the admin");
+    assert(config.fundOwner.equals(owner.publicKey), "Fees go to the admin");
+
+    // A config creator cannot touch fees
+    try {
+      await program.methods
+        .updateAmmConfig({ ...emptyParams, tradeFeeRate: new anchor.BN(3000) })
+        .accounts({ owner: configCreator.publicKey, ammConfig: configPDA } as any)
+        .signers([configCreator])
+        .rpc();
+      assert.fail("Config creator should not update fees");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+  });
+
+  it("lets a fee manager update fees but not pool creation", async () => {
+    const feeManager = Keypair.generate();
+    const configPDA = get_amm_config_pda({ index: configIndex, program_id: program.programId });
+
+    await program.methods
+      .updateRole({ feeManager: {} }, feeManager.publicKey, true)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+
+    await program.methods
+      .updateAmmConfig({ ...emptyParams, tradeFeeRate: new anchor.BN(3000) })
+      .accounts({ owner: feeManager.publicKey, ammConfig: configPDA } as any)
+      .signers([feeManager])
+      .rpc();
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert.equal(config.tradeFeeRate.toNumber(), 3000);
+
+    try {
+      await program.methods
+        .updateAmmConfig({ ...emptyParams, disableCreatePool: true })
+        .accounts({ owner: feeManager.publicKey, ammConfig: configPDA } as any)
+        .signers([feeManager])
+        .rpc();
+      assert.fail("Only the admin can disable pool creation");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+
+    await program.methods
+      .updateRole({ feeManager: {} }, feeManager.publicKey, false)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+    const registry = await program.account.roleRegistry.fetch(
+      PublicKey.findProgramAddressSync([Buffer.from("ROLE_REGISTRY")], program.programId)[0]
+    );
+    assert(!registry.feeManagers.some((key: PublicKey) => key.equals(feeManager.publicKey)));
+  });
+});
@@ -0,0 +1,291 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_pool_pda,
+  get_vault_pda,
+  get_lp_mint_pda,
+  get_authority_pda,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("close pool", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let lpMintPda: PublicKey;
+  let vault0Pda: PublicKey;
+  let vault1Pda: PublicKey;
+  let authorityPda: PublicKey;
+  let creatorToken0Ata: PublicKey;
+  let creatorToken1Ata: PublicKey;
+  let creatorLpAta: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 12000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    // 1. Create config
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // 2. Create mints
+    const mintA = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const mintB = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    [token0Mint, token1Mint] =
+      mintA.toBuffer().compare(mintB.toBuffer()) < 0
+        ? [mintA, mintB]
+        : [mintB, mintA];
+
+    // 3. Create ATAs and fund them
+    const token0Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token0Mint,
+      owner.publicKey
+    );
+    const token1Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token1Mint,
+      owner.publicKey
+    );
+    creatorToken0Ata = token0Account.address;
+    creatorToken1Ata = token1Account.address;
+
+    await mintTo(
+      provider.connection,
+      owner,
+      token0Mint,
+      creatorToken0Ata,
+      owner,
+      100_000_000_000
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      token1Mint,
+      creatorToken1Ata,
+      owner,
+      100_000_000_000
+    );
+
+    // 4. Derive pool PDAs
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+    lpMintPda = get_lp_mint_pda({ program_id: program.programId, pool: poolPDA });
+    vault0Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token0Mint,
+    });
+    vault1Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token1Mint,
+    });
+    authorityPda = get_authority_pda({ program_id: program.programId });
+    creatorLpAta = getAssociatedTokenAddressSync(lpMintPda, owner.publicKey);
+
+    // 5. Create pool with initial liquidity
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000), // 10B initial
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        creatorToken0: creatorToken0Ata,
+        creatorToken1: creatorToken1Ata,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+  });
+
+  const closeAccounts = (signer: PublicKey = owner.publicKey) => ({
+    owner: signer,
+    ammConfig: configPDA,
+    poolState: poolPDA,
+    poolCreator: owner.publicKey,
+    token0Mint,
+    token1Mint,
+    token0Vault: vault0Pda,
+    token1Vault: vault1Pda,
+    authority: authorityPda,
+    protocolOwner: owner.publicKey,
+    protocolToken0Account: creatorToken0Ata,
+    protocolToken1Account: creatorToken1Ata,
+    token0Program: TOKEN_PROGRAM_ID,
+    token1Program: TOKEN_PROGRAM_ID,
+  });
+
+  it("rejects closing a pool that still has liquidity", async () => {
+    try {
+      await program.methods
+        .closePool()
+        .accounts(closeAccounts() as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Pool should not close");
+    } catch (err: any) {
+      assert.include(err.toString(), "PoolNotEmpty");
+    }
+  });
+
+  it("rejects a signer that is neither the pool creator nor the admin", async () => {
+    const stranger = Keypair.generate();
+    try {
+      await program.methods
+        .closePool()
+        .accounts(closeAccounts(stranger.publicKey) as any)
+        .signers([stranger])
+        .rpc();
+      assert.fail("Only the pool creator or admin can close");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+  });
+
+  it("closes an emptied pool and sweeps the dust", async () => {
+    const lpBalance = await getAccount(provider.connection, creatorLpAta);
+    await program.methods
+      .withdraw(
+        new anchor.BN(lpBalance.amount.toString()),
+        new anchor.BN(1),
+        new anchor.BN(1),
+        false
+      )
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        authority: authorityPda,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        signerToken0: creatorToken0Ata,
+        signerToken1: creatorToken1Ata,
+        token0Vault: vault0Pda,
+        token1Vault: vault1Pda,
+        lpMint: lpMintPda,
+        signerLp: creatorLpAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const pool = await program.account.poolState.fetch(poolPDA);
+    assert.equal(pool.lpSupply.toNumber(), 100, "Only the locked LP is left");
+    const dust0 = (await getAccount(provider.connection, vault0Pda)).amount;
+    const user0Before = (await getAccount(provider.connection, creatorToken0Ata)).amount;
+
+    await program.methods
+      .closePool()
+      .accounts(closeAccounts() as any)
+      .signers([owner])
+      .rpc();
+
+    const user0After = (await getAccount(provider.connection, creatorToken0Ata)).amount;
+    assert.equal(user0After - user0Before, dust0, "Dust goes to the protocol owner");
+    assert.isNull(await provider.connection.getAccountInfo(poolPDA));
+    assert.isNull(await provider.connection.getAccountInfo(vault0Pda));
+    assert.isNull(await provider.connection.getAccountInfo(vault1Pda));
+  });
+
+  it("cannot create the same pair again under the config", async () => {
+    try {
+      await program.methods
+        .createPool(
+          configIndex,
+          new anchor.BN(10_000_000_000),
+          new anchor.BN(10_000_000_000),
+          new anchor.BN(0),
+          { bothToken: {} }
+        )
+        .accounts({
+          creator: owner.publicKey,
+          ammConfig: configPDA,
+          token0Mint: token0Mint,
+          token1Mint: token1Mint,
+          creatorToken0: creatorToken0Ata,
+          creatorToken1: creatorToken1Ata,
+          feeReceiver: owner.publicKey,
+          token0Program: TOKEN_PROGRAM_ID,
+          token1Program: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("The LP mint of the closed pool still exists");
+    } catch (err: any) {
+      const logs = (err.logs ?? []).join("\n");
+      assert.include(logs + err.toString(), "already in use");
+    }
+  });
+});
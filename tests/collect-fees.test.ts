@@ -1,7 +1,8 @@
 import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
-import { PublicKey } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
 import {
+  ensureAdminState,
   get_amm_config_pda,
   get_pool_pda,
   get_vault_pda,
@@ -39,6 +40,8 @@ describe("collect-fees", () => {
   const configIndex = (Math.floor(Date.now() / 1000) + 5000) % 65535;
 
   before(async () => {
+    await ensureAdminState(program, owner);
+
     // 1. Create config - owner is protocol_owner and fund_owner
     configPDA = get_amm_config_pda({
       index: configIndex,
@@ -140,7 +143,8 @@ describe("collect-fees", () => {
         configIndex,
         new anchor.BN(10_000_000_000),
         new anchor.BN(10_000_000_000),
-        new anchor.BN(0)
+        new anchor.BN(0),
+        { bothToken: {} }
       )
       .accounts({
         creator: owner.publicKey,
@@ -150,6 +154,8 @@ describe("collect-fees", () => {
         creatorToken0: userToken0Ata,
         creatorToken1: userToken1Ata,
         feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -160,7 +166,7 @@ describe("collect-fees", () => {
     // 6. Execute a swap to accumulate fees
     const amountIn = new anchor.BN(1_000_000_000); // 1B tokens (2.5% = 25M fees)
     await program.methods
-      .swap(amountIn, new anchor.BN(1))
+      .swap(amountIn, new anchor.BN(1), false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -172,7 +178,8 @@ describe("collect-fees", () => {
         inputTokenAccount: userToken0Ata,
         outputTokenAccount: userToken1Ata,
         authority: authorityPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -200,7 +207,8 @@ describe("collect-fees", () => {
         receiverToken0Account: userToken0Ata,
         receiverToken1Account: userToken1Ata,
         owner: owner.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
@@ -221,7 +229,7 @@ describe("collect-fees", () => {
   it("collects fund fees", async () => {
     // Execute another swap to accumulate more fees
     await program.methods
-      .swap(new anchor.BN(500_000_000), new anchor.BN(1))
+      .swap(new anchor.BN(500_000_000), new anchor.BN(1), false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -233,7 +241,8 @@ describe("collect-fees", () => {
         inputTokenAccount: userToken0Ata,
         outputTokenAccount: userToken1Ata,
         authority: authorityPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -255,7 +264,8 @@ describe("collect-fees", () => {
         receiverToken0Account: userToken0Ata,
         receiverToken1Account: userToken1Ata,
         owner: owner.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
@@ -272,7 +282,7 @@ describe("collect-fees", () => {
   it("collects creator fees", async () => {
     // Execute another swap to accumulate creator fees
     await program.methods
-      .swap(new anchor.BN(500_000_000), new anchor.BN(1))
+      .swap(new anchor.BN(500_000_000), new anchor.BN(1), false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -284,7 +294,8 @@ describe("collect-fees", () => {
         inputTokenAccount: userToken0Ata,
         outputTokenAccount: userToken1Ata,
         authority: authorityPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -305,7 +316,8 @@ describe("collect-fees", () => {
         receiverToken0Account: userToken0Ata,
         receiverToken1Account: userToken1Ata,
         creator: owner.publicKey,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
         associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
         systemProgram: SYSTEM_PROGRAM_ID,
       } as any)
@@ -318,4 +330,215 @@ describe("collect-fees", () => {
 
     console.log("Creator fee collection successful!");
   });
+
+  it("charges the creator fee only in the token the creator chose", async () => {
+    // Fresh pair on the same config, creator fee always in token1
+    const mints = [
+      await createMint(provider.connection, owner, owner.publicKey, null, 9),
+      await createMint(provider.connection, owner, owner.publicKey, null, 9),
+    ];
+    const [mint0, mint1] =
+      mints[0].toBuffer().compare(mints[1].toBuffer()) < 0
+        ? [mints[0], mints[1]]
+        : [mints[1], mints[0]];
+    const [ata0, ata1] = await Promise.all(
+      [mint0, mint1].map(async (mint) => {
+        const ata = (
+          await getOrCreateAssociatedTokenAccount(
+            provider.connection,
+            owner,
+            mint,
+            owner.publicKey
+          )
+        ).address;
+        await mintTo(provider.connection, owner, mint, ata, owner, 100_000_000_000);
+        return ata;
+      })
+    );
+    const pool = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0,
+      mint1,
+    });
+    const vault0 = get_vault_pda({ program_id: program.programId, pool, mint: mint0 });
+    const vault1 = get_vault_pda({ program_id: program.programId, pool, mint: mint1 });
+
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { onlyToken1: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint: mint0,
+        token1Mint: mint1,
+        creatorToken0: ata0,
+        creatorToken1: ata1,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    const swapIn = async (zeroForOne: boolean) => {
+      await program.methods
+        .swap(new anchor.BN(100_000_000), new anchor.BN(1), false)
+        .accounts({
+          signer: owner.publicKey,
+          poolState: pool,
+          ammConfig: configPDA,
+          inputTokenMint: zeroForOne ? mint0 : mint1,
+          outputTokenMint: zeroForOne ? mint1 : mint0,
+          inputVault: zeroForOne ? vault0 : vault1,
+          outputVault: zeroForOne ? vault1 : vault0,
+          inputTokenAccount: zeroForOne ? ata0 : ata1,
+          outputTokenAccount: zeroForOne ? ata1 : ata0,
+          authority: authorityPda,
+          inputTokenProgram: TOKEN_PROGRAM_ID,
+          outputTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+    };
+
+    // token0 -> token1 takes the creator fee from the token1 output
+    await swapIn(true);
+    let state = await program.account.poolState.fetch(pool);
+    assert.equal(state.creatorToken0Fee.toNumber(), 0, "No creator fee in token0");
+    assert(state.creatorToken1Fee.gtn(0), "Creator fee accrues in token1");
+
+    // token1 -> token0 takes it from the token1 input
+    const token1FeeBefore = state.creatorToken1Fee;
+    await swapIn(false);
+    state = await program.account.poolState.fetch(pool);
+    assert.equal(state.creatorToken0Fee.toNumber(), 0, "Still no creator fee in token0");
+    assert(state.creatorToken1Fee.gt(token1FeeBefore), "Creator fee accrues in token1");
+  });
+
+  it("charges the creator fee as a surcharge on the swap input", async () => {
+    const poolBefore = await program.account.poolState.fetch(poolPDA);
+    const user1Before = await getAccount(provider.connection, userToken1Ata);
+
+    // token1 -> token0 puts the token1 creator fee on the input side
+    await program.methods
+      .swapBaseOutput(new anchor.BN(100_000_000), new anchor.BN(200_000_000), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token1Mint,
+        outputTokenMint: token0Mint,
+        inputVault: vault1Pda,
+        outputVault: vault0Pda,
+        inputTokenAccount: userToken1Ata,
+        outputTokenAccount: userToken0Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const poolAfter = await program.account.poolState.fetch(poolPDA);
+    const user1After = await getAccount(provider.connection, userToken1Ata);
+    const spent = new anchor.BN((user1Before.amount - user1After.amount).toString());
+
+    // 10% of the whole input, rounded up, not 10% of the trade fee
+    const expected = spent.muln(100000).addn(999999).divn(1000000);
+    assert.equal(
+      poolAfter.creatorToken1Fee.sub(poolBefore.creatorToken1Fee).toString(),
+      expected.toString()
+    );
+  });
+
+  it("transfers the creator role in two steps", async () => {
+    const newCreator = Keypair.generate();
+
+    await program.methods
+      .proposePoolCreator(newCreator.publicKey)
+      .accounts({ owner: owner.publicKey, poolState: poolPDA } as any)
+      .signers([owner])
+      .rpc();
+
+    let pool = await program.account.poolState.fetch(poolPDA);
+    assert(pool.poolCreator.equals(owner.publicKey), "Role moves only on accept");
+    assert(pool.pendingPoolCreator.equals(newCreator.publicKey));
+
+    try {
+      await program.methods
+        .acceptPoolCreator()
+        .accounts({ newCreator: owner.publicKey, poolState: poolPDA } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Only the pending creator can accept");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+
+    await program.methods
+      .acceptPoolCreator()
+      .accounts({ newCreator: newCreator.publicKey, poolState: poolPDA } as any)
+      .signers([newCreator])
+      .rpc();
+
+    pool = await program.account.poolState.fetch(poolPDA);
+    assert(pool.poolCreator.equals(newCreator.publicKey));
+    assert(pool.pendingPoolCreator.equals(PublicKey.default));
+
+    // Hand it back so the renounce test runs as the wallet
+    await program.methods
+      .proposePoolCreator(owner.publicKey)
+      .accounts({ owner: newCreator.publicKey, poolState: poolPDA } as any)
+      .signers([newCreator])
+      .rpc();
+    await program.methods
+      .acceptPoolCreator()
+      .accounts({ newCreator: owner.publicKey, poolState: poolPDA } as any)
+      .signers([owner])
+      .rpc();
+  });
+
+  it("stops accruing creator fees once renounced", async () => {
+    await program.methods
+      .renouncePoolCreator()
+      .accounts({ owner: owner.publicKey, poolState: poolPDA } as any)
+      .signers([owner])
+      .rpc();
+
+    const poolBefore = await program.account.poolState.fetch(poolPDA);
+    assert.isFalse(poolBefore.creatorFeeActive);
+
+    await program.methods
+      .swap(new anchor.BN(100_000_000), new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token1Mint,
+        outputTokenMint: token0Mint,
+        inputVault: vault1Pda,
+        outputVault: vault0Pda,
+        inputTokenAccount: userToken1Ata,
+        outputTokenAccount: userToken0Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const poolAfter = await program.account.poolState.fetch(poolPDA);
+    assert.equal(
+      poolAfter.creatorToken1Fee.toString(),
+      poolBefore.creatorToken1Fee.toString(),
+      "The surcharge stays in the reserves for LPs"
+    );
+  });
 });
@@ -1,6 +1,6 @@
 import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
-import { get_amm_config_pda } from "./utils";
+import { ensureAdminState, get_amm_config_pda } from "./utils";
 import { assert } from "chai";
 
 describe("Create AMM config", () => {
@@ -10,6 +10,10 @@ describe("Create AMM config", () => {
   const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
   const owner = anchor.Wallet.local().payer;
 
+  before(async () => {
+    await ensureAdminState(program, owner);
+  });
+
   it("creates config with valid fee rates", async () => {
     const index = 0;
     const configPda = get_amm_config_pda({
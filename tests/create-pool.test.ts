@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
 import { PublicKey } from "@solana/web3.js";
 import {
+  ensureAdminState,
   get_amm_config_pda,
   get_authority_pda,
   get_lp_mint_pda,
@@ -14,7 +15,11 @@ import {
   getAssociatedTokenAddressSync,
   getOrCreateAssociatedTokenAccount,
   mintTo,
+  getAccount,
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
 } from "@solana/spl-token";
+import { assert } from "chai";
 
 describe("create_pool", () => {
   anchor.setProvider(anchor.AnchorProvider.env());
@@ -33,6 +38,8 @@ describe("create_pool", () => {
   let creatorToken1Ata: PublicKey;
 
   before(async () => {
+    await ensureAdminState(program, owner);
+
     configPDA = get_amm_config_pda({
       index: configIndex,
       program_id: program.programId,
@@ -157,7 +164,8 @@ describe("create_pool", () => {
         configIndex,
         new anchor.BN(1_000_000_000),
         new anchor.BN(1_000_000_000),
-        new anchor.BN(0)
+        new anchor.BN(0),
+        { bothToken: {} }
       )
       .accounts({
         creator: owner.publicKey,
@@ -167,10 +175,133 @@ describe("create_pool", () => {
         creatorToken0: creatorToken0Ata,
         creatorToken1: creatorToken1Ata,
         feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
 
     console.log("Pool created:", poolPda.toBase58());
   });
+
+  it("creates pool with one legacy token and one Token-2022 token", async () => {
+    const legacyMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const token2022Mint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      6,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const legacyIsToken0 =
+      legacyMint.toBuffer().compare(token2022Mint.toBuffer()) < 0;
+    const [mint0, mint1] = legacyIsToken0
+      ? [legacyMint, token2022Mint]
+      : [token2022Mint, legacyMint];
+    const [program0, program1] = legacyIsToken0
+      ? [TOKEN_PROGRAM_ID, TOKEN_2022_PROGRAM_ID]
+      : [TOKEN_2022_PROGRAM_ID, TOKEN_PROGRAM_ID];
+
+    const creator0 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      mint0,
+      owner.publicKey,
+      false,
+      undefined,
+      undefined,
+      program0
+    );
+    const creator1 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      mint1,
+      owner.publicKey,
+      false,
+      undefined,
+      undefined,
+      program1
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      mint0,
+      creator0.address,
+      owner,
+      10_000_000_000,
+      [],
+      undefined,
+      program0
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      mint1,
+      creator1.address,
+      owner,
+      10_000_000_000,
+      [],
+      undefined,
+      program1
+    );
+
+    const poolPda = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0,
+      mint1,
+    });
+
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        token0Mint: mint0,
+        token1Mint: mint1,
+        ammConfig: configPDA,
+        creatorToken0: creator0.address,
+        creatorToken1: creator1.address,
+        feeReceiver: owner.publicKey,
+        token0Program: program0,
+        token1Program: program1,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const pool = await program.account.poolState.fetch(poolPda);
+    assert.equal(pool.token0Program.toBase58(), program0.toBase58());
+    assert.equal(pool.token1Program.toBase58(), program1.toBase58());
+
+    // Each vault is owned by its mint's program
+    const vault0 = await getAccount(
+      provider.connection,
+      pool.token0Vault,
+      undefined,
+      program0
+    );
+    const vault1 = await getAccount(
+      provider.connection,
+      pool.token1Vault,
+      undefined,
+      program1
+    );
+    assert.equal(vault0.amount.toString(), "1000000000");
+    assert.equal(vault1.amount.toString(), "1000000000");
+  });
 });
@@ -1,7 +1,8 @@
 import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
-import { PublicKey } from "@solana/web3.js";
+import { Keypair, PublicKey } from "@solana/web3.js";
 import {
+  ensureAdminState,
   get_amm_config_pda,
   get_pool_pda,
   get_vault_pda,
@@ -41,6 +42,8 @@ describe("deposit", () => {
   const configIndex = (Math.floor(Date.now() / 1000) + 2000) % 65535;
 
   before(async () => {
+    await ensureAdminState(program, owner);
+
     // 1. Create config
     configPDA = get_amm_config_pda({
       index: configIndex,
@@ -144,7 +147,8 @@ describe("deposit", () => {
         configIndex,
         new anchor.BN(1_000_000_000),
         new anchor.BN(1_000_000_000),
-        new anchor.BN(0)
+        new anchor.BN(0),
+        { bothToken: {} }
       )
       .accounts({
         creator: owner.publicKey,
@@ -154,6 +158,8 @@ describe("deposit", () => {
         creatorToken0: creatorToken0Ata,
         creatorToken1: creatorToken1Ata,
         feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -177,7 +183,7 @@ describe("deposit", () => {
 
     // Execute deposit
     await program.methods
-      .deposit(lpAmountToDeposit, maxToken0, maxToken1)
+      .deposit(lpAmountToDeposit, maxToken0, maxToken1, false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -192,6 +198,8 @@ describe("deposit", () => {
         lpMint: lpMintPda,
         signerLp: creatorLpAta,
         tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -218,4 +226,227 @@ describe("deposit", () => {
     console.log("Vault0 increase:", (vault0After.amount - vault0Before.amount).toString());
     console.log("Vault1 increase:", (vault1After.amount - vault1Before.amount).toString());
   });
+
+  it("deposits an exact token0 amount and matches token1", async () => {
+    const lpBalanceBefore = await getAccount(provider.connection, creatorLpAta);
+    const user0Before = await getAccount(provider.connection, creatorToken0Ata);
+    const user1Before = await getAccount(provider.connection, creatorToken1Ata);
+
+    const amount = new anchor.BN(100_000_000);
+
+    await program.methods
+      .depositBaseInput(
+        true,
+        amount,
+        new anchor.BN(1_000_000_000), // maximum token1
+        new anchor.BN(1), // minimum LP
+        false
+      )
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        authority: authorityPda,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        signerToken0: creatorToken0Ata,
+        signerToken1: creatorToken1Ata,
+        token0Vault: vault0Pda,
+        token1Vault: vault1Pda,
+        lpMint: lpMintPda,
+        signerLp: creatorLpAta,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const lpBalanceAfter = await getAccount(provider.connection, creatorLpAta);
+    const user0After = await getAccount(provider.connection, creatorToken0Ata);
+    const user1After = await getAccount(provider.connection, creatorToken1Ata);
+
+    const token0Spent = user0Before.amount - user0After.amount;
+    assert(lpBalanceAfter.amount > lpBalanceBefore.amount, "Should receive LP tokens");
+    assert(token0Spent > 0n, "Should spend token0");
+    assert(token0Spent <= BigInt(amount.toString()), "Should not exceed the token0 amount");
+    assert(user1After.amount < user1Before.amount, "Should spend matching token1");
+  });
+
+  it("rejects an exact-amount deposit below the minimum LP", async () => {
+    try {
+      await program.methods
+        .depositBaseInput(
+          true,
+          new anchor.BN(1_000_000),
+          new anchor.BN(1_000_000_000),
+          new anchor.BN("18446744073709551615"),
+          false
+        )
+        .accounts({
+          signer: owner.publicKey,
+          poolState: poolPDA,
+          ammConfig: configPDA,
+          authority: authorityPda,
+          token0Mint: token0Mint,
+          token1Mint: token1Mint,
+          signerToken0: creatorToken0Ata,
+          signerToken1: creatorToken1Ata,
+          token0Vault: vault0Pda,
+          token1Vault: vault1Pda,
+          lpMint: lpMintPda,
+          signerLp: creatorLpAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token0Program: TOKEN_PROGRAM_ID,
+          token1Program: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Deposit should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+  });
+
+  it("deposits a single token and receives LP tokens", async () => {
+    const lpBalanceBefore = await getAccount(provider.connection, creatorLpAta);
+    const user0Before = await getAccount(provider.connection, creatorToken0Ata);
+    const user1Before = await getAccount(provider.connection, creatorToken1Ata);
+    const vault1Before = await getAccount(provider.connection, vault1Pda);
+
+    const amountIn = new anchor.BN(200_000_000);
+
+    await program.methods
+      .depositSingleToken(amountIn, new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token0Mint,
+        inputTokenAccount: creatorToken0Ata,
+        inputVault: vault0Pda,
+        outputVault: vault1Pda,
+        signerLp: creatorLpAta,
+        lpMint: lpMintPda,
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const lpBalanceAfter = await getAccount(provider.connection, creatorLpAta);
+    const user0After = await getAccount(provider.connection, creatorToken0Ata);
+    const user1After = await getAccount(provider.connection, creatorToken1Ata);
+    const vault1After = await getAccount(provider.connection, vault1Pda);
+
+    assert(lpBalanceAfter.amount > lpBalanceBefore.amount, "Should receive LP tokens");
+    assert.equal(
+      (user0Before.amount - user0After.amount).toString(),
+      amountIn.toString(),
+      "Should spend exactly the input amount"
+    );
+    assert.equal(user1After.amount, user1Before.amount, "Token1 should be untouched");
+    assert.equal(vault1After.amount, vault1Before.amount, "Swap output stays in the pool");
+  });
+
+  it("rejects deposits while the pool is paused", async () => {
+    const depositBit = 1 << 0; // PoolStatusBitIndex::Deposit
+
+    await program.methods
+      .updatePoolStatus(depositBit)
+      .accounts({
+        owner: owner.publicKey,
+        poolState: poolPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .deposit(
+          new anchor.BN(1_000_000),
+          new anchor.BN(1_000_000_000),
+          new anchor.BN(1_000_000_000),
+          false
+        )
+        .accounts({
+          signer: owner.publicKey,
+          poolState: poolPDA,
+          ammConfig: configPDA,
+          authority: authorityPda,
+          signerToken0: creatorToken0Ata,
+          signerToken1: creatorToken1Ata,
+          token0Vault: vault0Pda,
+          token1Vault: vault1Pda,
+          lpMint: lpMintPda,
+          signerLp: creatorLpAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token0Program: TOKEN_PROGRAM_ID,
+          token1Program: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Deposit should be disabled");
+    } catch (err: any) {
+      assert.include(err.toString(), "DepositDisabled");
+    }
+
+    // Re-enable everything
+    await program.methods
+      .updatePoolStatus(0)
+      .accounts({
+        owner: owner.publicKey,
+        poolState: poolPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const pool = await program.account.poolState.fetch(poolPDA);
+    assert.equal(pool.status, 0);
+  });
+
+  it("lets a pauser flip pool status bits", async () => {
+    const pauser = Keypair.generate();
+    const swapBit = 1 << 2; // PoolStatusBitIndex::Swap
+
+    await program.methods
+      .updateRole({ pauser: {} }, pauser.publicKey, true)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+
+    for (const status of [swapBit, 0]) {
+      await program.methods
+        .updatePoolStatus(status)
+        .accounts({
+          owner: pauser.publicKey,
+          poolState: poolPDA,
+        } as any)
+        .signers([pauser])
+        .rpc();
+      const pool = await program.account.poolState.fetch(poolPDA);
+      assert.equal(pool.status, status);
+    }
+
+    await program.methods
+      .updateRole({ pauser: {} }, pauser.publicKey, false)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .updatePoolStatus(swapBit)
+        .accounts({
+          owner: pauser.publicKey,
+          poolState: poolPDA,
+        } as any)
+        .signers([pauser])
+        .rpc();
+      assert.fail("Revoked pauser should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidOwner");
+    }
+  });
 });
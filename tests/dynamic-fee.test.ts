@@ -0,0 +1,229 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_pool_pda,
+  get_vault_pda,
+  get_authority_pda,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("dynamic fee", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let vault0Pda: PublicKey;
+  let vault1Pda: PublicKey;
+  let authorityPda: PublicKey;
+  let creatorToken0Ata: PublicKey;
+  let creatorToken1Ata: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 15000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    // 1. Create config
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000), // 10% of the trade fee, used to read the rate
+        new anchor.BN(0),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    await program.methods
+      .updateAmmConfig({
+        tradeFeeRate: null,
+        protocolFeeRate: null,
+        fundFeeRate: null,
+        creatorFeeRate: null,
+        createPoolFee: null,
+        protocolOwner: null,
+        fundOwner: null,
+        disableCreatePool: null,
+        dynamicFeeEnabled: true,
+        minTradeFeeRate: new anchor.BN(1000),
+        maxTradeFeeRate: new anchor.BN(20000),
+        volatilityDecayPeriod: new anchor.BN(300),
+        volatilityFeeFactor: new anchor.BN(500000),
+      })
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // 2. Create mints
+    const mintA = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const mintB = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    [token0Mint, token1Mint] =
+      mintA.toBuffer().compare(mintB.toBuffer()) < 0
+        ? [mintA, mintB]
+        : [mintB, mintA];
+
+    // 3. Create ATAs and fund them
+    const token0Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token0Mint,
+      owner.publicKey
+    );
+    const token1Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token1Mint,
+      owner.publicKey
+    );
+    creatorToken0Ata = token0Account.address;
+    creatorToken1Ata = token1Account.address;
+
+    await mintTo(
+      provider.connection,
+      owner,
+      token0Mint,
+      creatorToken0Ata,
+      owner,
+      100_000_000_000
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      token1Mint,
+      creatorToken1Ata,
+      owner,
+      100_000_000_000
+    );
+
+    // 4. Derive pool PDAs
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+    vault0Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token0Mint,
+    });
+    vault1Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token1Mint,
+    });
+    authorityPda = get_authority_pda({ program_id: program.programId });
+
+    // 5. Create pool with initial liquidity
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000), // 10B initial
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        creatorToken0: creatorToken0Ata,
+        creatorToken1: creatorToken1Ata,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+  });
+
+  const swap = async (amountIn: number) => {
+    await program.methods
+      .swap(new anchor.BN(amountIn), new anchor.BN(0), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token0Mint,
+        outputTokenMint: token1Mint,
+        inputVault: vault0Pda,
+        outputVault: vault1Pda,
+        inputTokenAccount: creatorToken0Ata,
+        outputTokenAccount: creatorToken1Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+  };
+
+  // Protocol fee accrued by a swap, 10% of its trade fee
+  const protocolFeeOf = async (amountIn: number) => {
+    const before = await program.account.poolState.fetch(poolPDA);
+    await swap(amountIn);
+    const after = await program.account.poolState.fetch(poolPDA);
+    return after.protocolToken0Fee.sub(before.protocolToken0Fee).toNumber();
+  };
+
+  it("raises the trade fee after a volatility spike, up to the maximum", async () => {
+    // Calm pool: base rate, 10M * 0.25% = 25_000 trade fee
+    const calmFee = await protocolFeeOf(10_000_000);
+    assert.equal(calmFee, 2_500);
+
+    // Half the pool's depth moves the price ~55%, far past the cap
+    await swap(5_000_000_000);
+
+    // Same trade now pays the 2% maximum: 10M * 2% = 200_000 trade fee
+    const spikeFee = await protocolFeeOf(10_000_000);
+    assert.isTrue(spikeFee > calmFee, "Fee rises with volatility");
+    assert.equal(spikeFee, 20_000, "Fee is capped at max_trade_fee_rate");
+  });
+});
@@ -0,0 +1,307 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_pool_pda,
+  get_vault_pda,
+  get_lp_mint_pda,
+  get_authority_pda,
+  get_locked_lp_vault_pda,
+  get_locked_position_pda,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("lock liquidity", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let lpMintPda: PublicKey;
+  let vault0Pda: PublicKey;
+  let vault1Pda: PublicKey;
+  let authorityPda: PublicKey;
+  let creatorToken0Ata: PublicKey;
+  let creatorToken1Ata: PublicKey;
+  let creatorLpAta: PublicKey;
+  let lockedLpVaultPda: PublicKey;
+  let lockedPositionPda: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 13000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    // 1. Create config
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // 2. Create mints
+    const mintA = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const mintB = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    [token0Mint, token1Mint] =
+      mintA.toBuffer().compare(mintB.toBuffer()) < 0
+        ? [mintA, mintB]
+        : [mintB, mintA];
+
+    // 3. Create ATAs and fund them
+    const token0Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token0Mint,
+      owner.publicKey
+    );
+    const token1Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token1Mint,
+      owner.publicKey
+    );
+    creatorToken0Ata = token0Account.address;
+    creatorToken1Ata = token1Account.address;
+
+    await mintTo(
+      provider.connection,
+      owner,
+      token0Mint,
+      creatorToken0Ata,
+      owner,
+      100_000_000_000
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      token1Mint,
+      creatorToken1Ata,
+      owner,
+      100_000_000_000
+    );
+
+    // 4. Derive pool PDAs
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+    lpMintPda = get_lp_mint_pda({ program_id: program.programId, pool: poolPDA });
+    vault0Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token0Mint,
+    });
+    vault1Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token1Mint,
+    });
+    authorityPda = get_authority_pda({ program_id: program.programId });
+    creatorLpAta = getAssociatedTokenAddressSync(lpMintPda, owner.publicKey);
+    lockedLpVaultPda = get_locked_lp_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+    });
+    lockedPositionPda = get_locked_position_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      owner: owner.publicKey,
+    });
+
+    // 5. Create pool with initial liquidity
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000), // 10B initial
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        creatorToken0: creatorToken0Ata,
+        creatorToken1: creatorToken1Ata,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+  });
+
+  const lockAmount = new anchor.BN(1_000_000_000);
+
+  const swap = async (amountIn: number) => {
+    await program.methods
+      .swap(new anchor.BN(amountIn), new anchor.BN(0), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token0Mint,
+        outputTokenMint: token1Mint,
+        inputVault: vault0Pda,
+        outputVault: vault1Pda,
+        inputTokenAccount: creatorToken0Ata,
+        outputTokenAccount: creatorToken1Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+  };
+
+  const collect = () =>
+    program.methods
+      .collectLockedFee()
+      .accounts({
+        owner: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        lockedPosition: lockedPositionPda,
+        lockedLpVault: lockedLpVaultPda,
+        lpMint: lpMintPda,
+        token0Mint,
+        token1Mint,
+        token0Vault: vault0Pda,
+        token1Vault: vault1Pda,
+        ownerToken0: creatorToken0Ata,
+        ownerToken1: creatorToken1Ata,
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+  it("locks LP into the pool's locked LP vault", async () => {
+    const lpBefore = (await getAccount(provider.connection, creatorLpAta)).amount;
+
+    await program.methods
+      .lockLiquidity(lockAmount)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        token0Vault: vault0Pda,
+        token1Vault: vault1Pda,
+        lpMint: lpMintPda,
+        signerLp: creatorLpAta,
+        lockedLpVault: lockedLpVaultPda,
+        lockedPosition: lockedPositionPda,
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const lpAfter = (await getAccount(provider.connection, creatorLpAta)).amount;
+    assert.equal(lpBefore - lpAfter, BigInt(lockAmount.toString()));
+
+    const vault = await getAccount(provider.connection, lockedLpVaultPda);
+    assert.equal(vault.amount, BigInt(lockAmount.toString()));
+    assert.isTrue(vault.owner.equals(authorityPda), "Only the program controls it");
+
+    const position = await program.account.lockedPosition.fetch(lockedPositionPda);
+    assert.isTrue(position.owner.equals(owner.publicKey));
+    assert.equal(position.lpAmount.toString(), lockAmount.toString());
+  });
+
+  it("rejects collecting before any fees accrue", async () => {
+    try {
+      await collect();
+      assert.fail("Nothing should be collectable yet");
+    } catch (err: any) {
+      assert.include(err.toString(), "NoFeesToCollect");
+    }
+  });
+
+  it("pays out fees earned since locking and keeps the locked share", async () => {
+    await swap(1_000_000_000);
+    await swap(1_000_000_000);
+
+    const positionBefore = await program.account.lockedPosition.fetch(lockedPositionPda);
+    const user0Before = (await getAccount(provider.connection, creatorToken0Ata)).amount;
+    const user1Before = (await getAccount(provider.connection, creatorToken1Ata)).amount;
+
+    await collect();
+
+    const positionAfter = await program.account.lockedPosition.fetch(lockedPositionPda);
+    const burned = positionBefore.lpAmount.sub(positionAfter.lpAmount);
+    assert.isTrue(burned.gtn(0), "Fee share is burned from the position");
+    assert.equal(
+      positionAfter.lockedLiquidity.toString(),
+      positionBefore.lockedLiquidity.toString(),
+      "Locked liquidity never changes"
+    );
+
+    const vault = await getAccount(provider.connection, lockedLpVaultPda);
+    assert.equal(vault.amount.toString(), positionAfter.lpAmount.toString());
+
+    const user0After = (await getAccount(provider.connection, creatorToken0Ata)).amount;
+    const user1After = (await getAccount(provider.connection, creatorToken1Ata)).amount;
+    assert.isTrue(user0After > user0Before, "Token 0 fees received");
+    assert.isTrue(user1After > user1Before, "Token 1 fees received");
+
+    // Everything earned was paid, a second claim has nothing left
+    try {
+      await collect();
+      assert.fail("Fees were already collected");
+    } catch (err: any) {
+      assert.include(err.toString(), "NoFeesToCollect");
+    }
+  });
+});
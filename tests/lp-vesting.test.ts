@@ -0,0 +1,344 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { Keypair, PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_pool_pda,
+  get_vault_pda,
+  get_lp_mint_pda,
+  get_authority_pda,
+  get_lp_vesting_pda,
+  get_lp_vesting_vault_pda,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+  getAccount,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("lp vesting", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let lpMintPda: PublicKey;
+  let vault0Pda: PublicKey;
+  let vault1Pda: PublicKey;
+  let authorityPda: PublicKey;
+  let creatorToken0Ata: PublicKey;
+  let creatorToken1Ata: PublicKey;
+  let creatorLpAta: PublicKey;
+  const beneficiary = Keypair.generate();
+  let beneficiaryLpAta: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 14000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    // 1. Create config
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // 2. Create mints
+    const mintA = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    const mintB = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    [token0Mint, token1Mint] =
+      mintA.toBuffer().compare(mintB.toBuffer()) < 0
+        ? [mintA, mintB]
+        : [mintB, mintA];
+
+    // 3. Create ATAs and fund them
+    const token0Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token0Mint,
+      owner.publicKey
+    );
+    const token1Account = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token1Mint,
+      owner.publicKey
+    );
+    creatorToken0Ata = token0Account.address;
+    creatorToken1Ata = token1Account.address;
+
+    await mintTo(
+      provider.connection,
+      owner,
+      token0Mint,
+      creatorToken0Ata,
+      owner,
+      100_000_000_000
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      token1Mint,
+      creatorToken1Ata,
+      owner,
+      100_000_000_000
+    );
+
+    // 4. Derive pool PDAs
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+    lpMintPda = get_lp_mint_pda({ program_id: program.programId, pool: poolPDA });
+    vault0Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token0Mint,
+    });
+    vault1Pda = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: token1Mint,
+    });
+    authorityPda = get_authority_pda({ program_id: program.programId });
+    creatorLpAta = getAssociatedTokenAddressSync(lpMintPda, owner.publicKey);
+
+    // 5. Create pool with initial liquidity
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000), // 10B initial
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint: token0Mint,
+        token1Mint: token1Mint,
+        creatorToken0: creatorToken0Ata,
+        creatorToken1: creatorToken1Ata,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+
+    beneficiaryLpAta = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        owner,
+        lpMintPda,
+        beneficiary.publicKey
+      )
+    ).address;
+  });
+
+  const lockAmount = new anchor.BN(1_000_000_000);
+
+  const vestingPdas = (beneficiaryKey: PublicKey, nonce: number) => {
+    const lpVesting = get_lp_vesting_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      beneficiary: beneficiaryKey,
+      funder: owner.publicKey,
+      nonce,
+    });
+    const lpVestingVault = get_lp_vesting_vault_pda({
+      program_id: program.programId,
+      lp_vesting: lpVesting,
+    });
+    return { lpVesting, lpVestingVault };
+  };
+
+  const lock = (
+    beneficiaryKey: PublicKey,
+    nonce: number,
+    cliffTime: number,
+    endTime: number
+  ) =>
+    program.methods
+      .lockLpVesting(
+        new anchor.BN(nonce),
+        lockAmount,
+        new anchor.BN(cliffTime),
+        new anchor.BN(endTime)
+      )
+      .accounts({
+        signer: owner.publicKey,
+        beneficiary: beneficiaryKey,
+        poolState: poolPDA,
+        lpMint: lpMintPda,
+        signerLp: creatorLpAta,
+        ...vestingPdas(beneficiaryKey, nonce),
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+  const claim = (signer: Keypair, nonce: number, beneficiaryLp: PublicKey) =>
+    program.methods
+      .claimVestedLp()
+      .accounts({
+        beneficiary: signer.publicKey,
+        poolState: poolPDA,
+        lpMint: lpMintPda,
+        ...vestingPdas(signer.publicKey, nonce),
+        beneficiaryLp,
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([signer])
+      .rpc();
+
+  const close = (beneficiaryKey: PublicKey, nonce: number) =>
+    program.methods
+      .closeLpVesting()
+      .accounts({
+        owner: owner.publicKey,
+        funder: owner.publicKey,
+        poolState: poolPDA,
+        ...vestingPdas(beneficiaryKey, nonce),
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+  it("rejects a schedule that ends before its cliff", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    try {
+      await lock(owner.publicKey, 0, now + 3600, now + 60);
+      assert.fail("Schedule should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidVestingSchedule");
+    }
+  });
+
+  it("holds the LP until the cliff", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await lock(owner.publicKey, 0, now + 3600, now + 3600);
+
+    const { lpVesting, lpVestingVault } = vestingPdas(owner.publicKey, 0);
+    const vault = await getAccount(provider.connection, lpVestingVault);
+    assert.equal(vault.amount, BigInt(lockAmount.toString()));
+    const escrow = await program.account.lpVesting.fetch(lpVesting);
+    assert.equal(escrow.cliffTime.toNumber(), now + 3600);
+
+    try {
+      await claim(owner, 0, creatorLpAta);
+      assert.fail("Nothing should be vested before the cliff");
+    } catch (err: any) {
+      assert.include(err.toString(), "NoVestedLp");
+    }
+  });
+
+  it("starts vesting at lock time on a pool that is already open", async () => {
+    const now = Math.floor(Date.now() / 1000);
+    await lock(beneficiary.publicKey, 0, 0, now + 3600);
+
+    const pool = await program.account.poolState.fetch(poolPDA);
+    const escrow = await program.account.lpVesting.fetch(
+      vestingPdas(beneficiary.publicKey, 0).lpVesting
+    );
+    assert.isTrue(escrow.startTime.gt(pool.openTime), "Not backdated to open_time");
+    assert.equal(
+      escrow.cliffTime.toString(),
+      escrow.startTime.toString(),
+      "Cliff moved up to the start"
+    );
+
+    // Only the few seconds since locking have vested
+    try {
+      await claim(beneficiary, 0, beneficiaryLpAta);
+    } catch (err: any) {
+      assert.include(err.toString(), "NoVestedLp");
+    }
+    const lp = await getAccount(provider.connection, beneficiaryLpAta);
+    assert.isTrue(lp.amount < BigInt(lockAmount.toString()) / BigInt(100));
+
+    try {
+      await close(beneficiary.publicKey, 0);
+      assert.fail("An escrow with unclaimed LP can't be closed");
+    } catch (err: any) {
+      assert.include(err.toString(), "LpVestingNotClaimed");
+    }
+  });
+
+  it("opens a second escrow for the same beneficiary and closes it once claimed", async () => {
+    const lpBefore = (await getAccount(provider.connection, beneficiaryLpAta)).amount;
+    const now = Math.floor(Date.now() / 1000);
+    await lock(beneficiary.publicKey, 1, 0, now + 5);
+
+    await new Promise((resolve) => setTimeout(resolve, 8000));
+    await claim(beneficiary, 1, beneficiaryLpAta);
+    const lpAfter = (await getAccount(provider.connection, beneficiaryLpAta)).amount;
+    assert.equal(lpAfter - lpBefore, BigInt(lockAmount.toString()));
+
+    try {
+      await claim(beneficiary, 1, beneficiaryLpAta);
+      assert.fail("Everything was already claimed");
+    } catch (err: any) {
+      assert.include(err.toString(), "NoVestedLp");
+    }
+
+    await close(beneficiary.publicKey, 1);
+    const { lpVesting, lpVestingVault } = vestingPdas(beneficiary.publicKey, 1);
+    assert.isNull(await provider.connection.getAccountInfo(lpVesting));
+    assert.isNull(await provider.connection.getAccountInfo(lpVestingVault));
+
+    // The first escrow is untouched
+    const first = await program.account.lpVesting.fetch(
+      vestingPdas(beneficiary.publicKey, 0).lpVesting
+    );
+    assert.equal(first.totalAmount.toString(), lockAmount.toString());
+  });
+});
@@ -0,0 +1,194 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import { ensureAdminState, get_amm_config_pda, orderMints } from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createInitializeMintInstruction,
+  createInitializePermanentDelegateInstruction,
+  createMint,
+  ExtensionType,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("mint extension checks", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let delegateMint: PublicKey;
+  let plainMint: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 8000) % 65535;
+
+  const createPool = async (mintAllowlist: PublicKey | null) => {
+    const [token0Mint, token1Mint] = orderMints(delegateMint, plainMint);
+    const programFor = (mint: PublicKey) =>
+      mint.equals(delegateMint) ? TOKEN_2022_PROGRAM_ID : TOKEN_PROGRAM_ID;
+
+    const creatorToken0 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token0Mint,
+      owner.publicKey,
+      false,
+      undefined,
+      undefined,
+      programFor(token0Mint)
+    );
+    const creatorToken1 = await getOrCreateAssociatedTokenAccount(
+      provider.connection,
+      owner,
+      token1Mint,
+      owner.publicKey,
+      false,
+      undefined,
+      undefined,
+      programFor(token1Mint)
+    );
+
+    for (const [mint, ata] of [
+      [token0Mint, creatorToken0.address],
+      [token1Mint, creatorToken1.address],
+    ]) {
+      await mintTo(
+        provider.connection,
+        owner,
+        mint,
+        ata,
+        owner,
+        10_000_000_000,
+        [],
+        undefined,
+        programFor(mint)
+      );
+    }
+
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint,
+        token1Mint,
+        creatorToken0: creatorToken0.address,
+        creatorToken1: creatorToken1.address,
+        feeReceiver: owner.publicKey,
+        token0Program: programFor(token0Mint),
+        token1Program: programFor(token1Mint),
+        mintAllowlist,
+      } as any)
+      .signers([owner])
+      .rpc();
+  };
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000),
+        new anchor.BN(250000),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // Token-2022 mint with a PermanentDelegate extension
+    const mintKeypair = Keypair.generate();
+    delegateMint = mintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.PermanentDelegate]);
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: owner.publicKey,
+          newAccountPubkey: delegateMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializePermanentDelegateInstruction(
+          delegateMint,
+          owner.publicKey,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          delegateMint,
+          9,
+          owner.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [owner, mintKeypair]
+    );
+
+    plainMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+  });
+
+  it("rejects a mint with a permanent delegate", async () => {
+    try {
+      await createPool(null);
+      assert.fail("Pool creation should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "PermanentDelegateNotAllowed");
+    }
+  });
+
+  it("accepts the mint once it is allowlisted", async () => {
+    await program.methods
+      .updateMintAllowlist(delegateMint, true)
+      .accounts({
+        owner: owner.publicKey,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const [mintAllowlistPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from("MINT_ALLOWLIST")],
+      program.programId
+    );
+    await createPool(mintAllowlistPda);
+  });
+});
@@ -0,0 +1,254 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_authority_pda,
+  get_lp_mint_pda,
+  get_pool_pda,
+  get_vault_pda,
+  orderMints,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  createWrappedNativeAccount,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  NATIVE_MINT,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("native SOL", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let tokenMint: PublicKey;
+  let userTokenAta: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let wsolIsToken0: boolean;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 9000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000),
+        new anchor.BN(250000),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    tokenMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+    userTokenAta = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        owner,
+        tokenMint,
+        owner.publicKey
+      )
+    ).address;
+    await mintTo(
+      provider.connection,
+      owner,
+      tokenMint,
+      userTokenAta,
+      owner,
+      10_000_000_000
+    );
+
+    // Seed the pool from a one-off wSOL account
+    const creatorWsol = await createWrappedNativeAccount(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      1_000_000_000,
+      anchor.web3.Keypair.generate()
+    );
+
+    [token0Mint, token1Mint] = orderMints(NATIVE_MINT, tokenMint);
+    wsolIsToken0 = token0Mint.equals(NATIVE_MINT);
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint,
+        token1Mint,
+        creatorToken0: wsolIsToken0 ? creatorWsol : userTokenAta,
+        creatorToken1: wsolIsToken0 ? userTokenAta : creatorWsol,
+        feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+  });
+
+  const wsolAta = () =>
+    getAssociatedTokenAddressSync(NATIVE_MINT, owner.publicKey);
+  const vault = (mint: PublicKey) =>
+    get_vault_pda({ program_id: program.programId, pool: poolPDA, mint });
+
+  const swap = (solIn: boolean, amountIn: number) => {
+    const [inputMint, outputMint] = solIn
+      ? [NATIVE_MINT, tokenMint]
+      : [tokenMint, NATIVE_MINT];
+    return program.methods
+      .swap(new anchor.BN(amountIn), new anchor.BN(1), true)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: inputMint,
+        outputTokenMint: outputMint,
+        inputVault: vault(inputMint),
+        outputVault: vault(outputMint),
+        inputTokenAccount: solIn ? wsolAta() : userTokenAta,
+        outputTokenAccount: solIn ? userTokenAta : wsolAta(),
+        authority: get_authority_pda({ program_id: program.programId }),
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any);
+  };
+
+  const liquidityAccounts = () => ({
+    signer: owner.publicKey,
+    poolState: poolPDA,
+    ammConfig: configPDA,
+    authority: get_authority_pda({ program_id: program.programId }),
+    token0Mint,
+    token1Mint,
+    signerToken0: wsolIsToken0 ? wsolAta() : userTokenAta,
+    signerToken1: wsolIsToken0 ? userTokenAta : wsolAta(),
+    token0Vault: vault(token0Mint),
+    token1Vault: vault(token1Mint),
+    lpMint: get_lp_mint_pda({ program_id: program.programId, pool: poolPDA }),
+    signerLp: getAssociatedTokenAddressSync(
+      get_lp_mint_pda({ program_id: program.programId, pool: poolPDA }),
+      owner.publicKey
+    ),
+    tokenProgram: TOKEN_PROGRAM_ID,
+    token0Program: TOKEN_PROGRAM_ID,
+    token1Program: TOKEN_PROGRAM_ID,
+  });
+
+  it("swaps SOL in without an existing wSOL account", async () => {
+    assert.isNull(await provider.connection.getAccountInfo(wsolAta()));
+    await swap(true, 100_000_000)
+      .signers([owner])
+      .rpc();
+
+    // The temporary wSOL account is closed once the swap settles
+    const wsolAccount = await provider.connection.getAccountInfo(wsolAta());
+    assert.isNull(wsolAccount, "wSOL account should be closed");
+  });
+
+  it("swaps out to SOL and closes the temporary wSOL account", async () => {
+    const lamportsBefore = await provider.connection.getBalance(owner.publicKey);
+
+    await swap(false, 100_000_000)
+      .signers([owner])
+      .rpc();
+
+    const lamportsAfter = await provider.connection.getBalance(owner.publicKey);
+    assert.isTrue(lamportsAfter > lamportsBefore, "Output arrives as SOL");
+    assert.isNull(await provider.connection.getAccountInfo(wsolAta()));
+  });
+
+  it("deposits SOL and withdraws back to SOL", async () => {
+    const lpAmount = new anchor.BN(10_000_000);
+    await program.methods
+      .deposit(
+        lpAmount,
+        new anchor.BN(1_000_000_000),
+        new anchor.BN(1_000_000_000),
+        true
+      )
+      .accounts(liquidityAccounts() as any)
+      .signers([owner])
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(wsolAta()));
+
+    const lamportsBefore = await provider.connection.getBalance(owner.publicKey);
+    await program.methods
+      .withdraw(lpAmount, new anchor.BN(1), new anchor.BN(1), true)
+      .accounts(liquidityAccounts() as any)
+      .signers([owner])
+      .rpc();
+
+    const lamportsAfter = await provider.connection.getBalance(owner.publicKey);
+    assert.isTrue(
+      lamportsAfter > lamportsBefore,
+      "Withdrawn side arrives as SOL"
+    );
+    assert.isNull(await provider.connection.getAccountInfo(wsolAta()));
+  });
+
+  it("leaves a wSOL account that already held a balance open", async () => {
+    const existing = BigInt(50_000_000);
+    await createWrappedNativeAccount(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      Number(existing)
+    );
+
+    // SOL in: only the swap's own lamports are wrapped and spent
+    await swap(true, 10_000_000).signers([owner]).rpc();
+    const wsolBefore = await getAccount(provider.connection, wsolAta());
+    assert.equal(wsolBefore.amount, existing);
+
+    // SOL out: the output stays wrapped rather than sweeping the account
+    await swap(false, 10_000_000).signers([owner]).rpc();
+    const wsol = await getAccount(provider.connection, wsolAta());
+    assert.isTrue(wsol.amount > existing, "Output is added to the wSOL balance");
+  });
+});
@@ -0,0 +1,262 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { PublicKey } from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_authority_pda,
+  get_pool_pda,
+  get_vault_pda,
+  orderMints,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createMint,
+  getAccount,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("swap route", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  // A -> B -> C through two pools
+  const mints: PublicKey[] = [];
+  const userAtas: PublicKey[] = [];
+  const pools: PublicKey[] = [];
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 10000) % 65535;
+
+  const hopAccounts = (pool: PublicKey, inputMint: PublicKey, outputMint: PublicKey) => [
+    { pubkey: configPDA, isSigner: false, isWritable: false },
+    { pubkey: pool, isSigner: false, isWritable: true },
+    {
+      pubkey: get_vault_pda({ program_id: program.programId, pool, mint: inputMint }),
+      isSigner: false,
+      isWritable: true,
+    },
+    {
+      pubkey: get_vault_pda({ program_id: program.programId, pool, mint: outputMint }),
+      isSigner: false,
+      isWritable: true,
+    },
+    { pubkey: outputMint, isSigner: false, isWritable: false },
+    { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+  ];
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000),
+        new anchor.BN(250000),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    for (let i = 0; i < 3; i++) {
+      const mint = await createMint(
+        provider.connection,
+        owner,
+        owner.publicKey,
+        null,
+        9
+      );
+      const ata = (
+        await getOrCreateAssociatedTokenAccount(
+          provider.connection,
+          owner,
+          mint,
+          owner.publicKey
+        )
+      ).address;
+      await mintTo(provider.connection, owner, mint, ata, owner, 100_000_000_000);
+      mints.push(mint);
+      userAtas.push(ata);
+    }
+
+    for (const [a, b] of [
+      [0, 1],
+      [1, 2],
+    ]) {
+      const [token0Mint, token1Mint] = orderMints(mints[a], mints[b]);
+      const ataFor = (mint: PublicKey) => userAtas[mints.findIndex((m) => m.equals(mint))];
+      const pool = get_pool_pda({
+        program_id: program.programId,
+        config_pda: configPDA,
+        mint0: token0Mint,
+        mint1: token1Mint,
+      });
+
+      await program.methods
+        .createPool(
+          configIndex,
+          new anchor.BN(10_000_000_000),
+          new anchor.BN(10_000_000_000),
+          new anchor.BN(0),
+          { bothToken: {} }
+        )
+        .accounts({
+          creator: owner.publicKey,
+          ammConfig: configPDA,
+          token0Mint,
+          token1Mint,
+          creatorToken0: ataFor(token0Mint),
+          creatorToken1: ataFor(token1Mint),
+          feeReceiver: owner.publicKey,
+          token0Program: TOKEN_PROGRAM_ID,
+          token1Program: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      pools.push(pool);
+    }
+
+    // Wait for pools to open
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+  });
+
+  const routeAccounts = () => ({
+    signer: owner.publicKey,
+    inputTokenMint: mints[0],
+    outputTokenMint: mints[2],
+    inputTokenAccount: userAtas[0],
+    outputTokenAccount: userAtas[2],
+    authority: get_authority_pda({ program_id: program.programId }),
+    inputTokenProgram: TOKEN_PROGRAM_ID,
+    outputTokenProgram: TOKEN_PROGRAM_ID,
+  });
+
+  it("routes A -> B -> C in one instruction", async () => {
+    const userABefore = await getAccount(provider.connection, userAtas[0]);
+    const userBBefore = await getAccount(provider.connection, userAtas[1]);
+    const userCBefore = await getAccount(provider.connection, userAtas[2]);
+
+    const amountIn = new anchor.BN(100_000_000);
+    await program.methods
+      .swapRoute(amountIn, new anchor.BN(1), false)
+      .accounts(routeAccounts() as any)
+      .remainingAccounts([
+        ...hopAccounts(pools[0], mints[0], mints[1]),
+        ...hopAccounts(pools[1], mints[1], mints[2]),
+      ])
+      .signers([owner])
+      .rpc();
+
+    const userAAfter = await getAccount(provider.connection, userAtas[0]);
+    const userBAfter = await getAccount(provider.connection, userAtas[1]);
+    const userCAfter = await getAccount(provider.connection, userAtas[2]);
+
+    assert.equal(
+      (userABefore.amount - userAAfter.amount).toString(),
+      amountIn.toString(),
+      "Should spend exact input amount"
+    );
+    assert.equal(userBAfter.amount, userBBefore.amount, "Intermediate token never reaches the user");
+    assert(userCAfter.amount > userCBefore.amount, "Should receive token C");
+
+    const pool0 = await program.account.poolState.fetch(pools[0]);
+    const pool1 = await program.account.poolState.fetch(pools[1]);
+    const protocolFee = (pool: any) => pool.protocolToken0Fee.add(pool.protocolToken1Fee);
+    assert(protocolFee(pool0).gtn(0), "First hop should accrue fees");
+    assert(protocolFee(pool1).gtn(0), "Second hop should accrue fees");
+  });
+
+  it("checks slippage only on the final output", async () => {
+    try {
+      await program.methods
+        .swapRoute(new anchor.BN(100_000_000), new anchor.BN(100_000_000), false)
+        .accounts(routeAccounts() as any)
+        .remainingAccounts([
+          ...hopAccounts(pools[0], mints[0], mints[1]),
+          ...hopAccounts(pools[1], mints[1], mints[2]),
+        ])
+        .signers([owner])
+        .rpc();
+      assert.fail("Route should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+  });
+
+  it("routes A -> B -> C for an exact output", async () => {
+    const userABefore = await getAccount(provider.connection, userAtas[0]);
+    const userCBefore = await getAccount(provider.connection, userAtas[2]);
+
+    const amountOut = new anchor.BN(50_000_000);
+    const maximumAmountIn = new anchor.BN(100_000_000);
+    await program.methods
+      .swapRouteBaseOutput(amountOut, maximumAmountIn, false)
+      .accounts(routeAccounts() as any)
+      .remainingAccounts([
+        ...hopAccounts(pools[0], mints[0], mints[1]),
+        ...hopAccounts(pools[1], mints[1], mints[2]),
+      ])
+      .signers([owner])
+      .rpc();
+
+    const userAAfter = await getAccount(provider.connection, userAtas[0]);
+    const userCAfter = await getAccount(provider.connection, userAtas[2]);
+    const spent = userABefore.amount - userAAfter.amount;
+
+    assert.equal(
+      (userCAfter.amount - userCBefore.amount).toString(),
+      amountOut.toString(),
+      "Should receive exact output amount"
+    );
+    assert(spent > BigInt(amountOut.toString()), "Two hops of fees cost more than 1:1");
+    assert(spent <= BigInt(maximumAmountIn.toString()), "Should not exceed max input");
+  });
+
+  it("rejects an exact output route above the maximum input", async () => {
+    try {
+      await program.methods
+        .swapRouteBaseOutput(new anchor.BN(50_000_000), new anchor.BN(50_000_000), false)
+        .accounts(routeAccounts() as any)
+        .remainingAccounts([
+          ...hopAccounts(pools[0], mints[0], mints[1]),
+          ...hopAccounts(pools[1], mints[1], mints[2]),
+        ])
+        .signers([owner])
+        .rpc();
+      assert.fail("Route should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "SlippageExceeded");
+    }
+  });
+
+  it("rejects a route that does not end in the output mint", async () => {
+    try {
+      await program.methods
+        .swapRoute(new anchor.BN(100_000_000), new anchor.BN(1), false)
+        .accounts(routeAccounts() as any)
+        .remainingAccounts(hopAccounts(pools[0], mints[0], mints[1]))
+        .signers([owner])
+        .rpc();
+      assert.fail("Route should be rejected");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidRoute");
+    }
+  });
+});
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
 import { PublicKey } from "@solana/web3.js";
 import {
+  ensureAdminState,
   get_amm_config_pda,
   get_pool_pda,
   get_vault_pda,
@@ -38,6 +39,8 @@ describe("swap", () => {
   const configIndex = (Math.floor(Date.now() / 1000) + 4000) % 65535;
 
   before(async () => {
+    await ensureAdminState(program, owner);
+
     // 1. Create config
     configPDA = get_amm_config_pda({
       index: configIndex,
@@ -140,7 +143,8 @@ describe("swap", () => {
         configIndex,
         new anchor.BN(10_000_000_000),
         new anchor.BN(10_000_000_000),
-        new anchor.BN(0)
+        new anchor.BN(0),
+        { bothToken: {} }
       )
       .accounts({
         creator: owner.publicKey,
@@ -150,6 +154,8 @@ describe("swap", () => {
         creatorToken0: userToken0Ata,
         creatorToken1: userToken1Ata,
         feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -172,7 +178,7 @@ describe("swap", () => {
 
     // Execute swap: token0 -> token1
     await program.methods
-      .swap(amountIn, minimumAmountOut)
+      .swap(amountIn, minimumAmountOut, false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -184,7 +190,8 @@ describe("swap", () => {
         inputTokenAccount: userToken0Ata,
         outputTokenAccount: userToken1Ata,
         authority: authorityPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -221,7 +228,7 @@ describe("swap", () => {
 
     // Execute swap: token1 -> token0 (want exact output of token0)
     await program.methods
-      .swapBaseOutput(amountOut, maximumAmountIn)
+      .swapBaseOutput(amountOut, maximumAmountIn, false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -233,7 +240,8 @@ describe("swap", () => {
         inputTokenAccount: userToken1Ata,
         outputTokenAccount: userToken0Ata,
         authority: authorityPda,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -254,4 +262,258 @@ describe("swap", () => {
 
     console.log("Swap base output successful!");
   });
+
+  it("emits a SwapEvent with the fee breakdown", async () => {
+    let event: any = null;
+    const listener = program.addEventListener("swapEvent", (e) => {
+      event = e;
+    });
+
+    const amountIn = new anchor.BN(10_000_000);
+    await program.methods
+      .swap(amountIn, new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token0Mint,
+        outputTokenMint: token1Mint,
+        inputVault: vault0Pda,
+        outputVault: vault1Pda,
+        inputTokenAccount: userToken0Ata,
+        outputTokenAccount: userToken1Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Give the websocket a moment to deliver the log
+    await new Promise((resolve) => setTimeout(resolve, 1000));
+    await program.removeEventListener(listener);
+
+    assert(event, "SwapEvent should be emitted");
+    assert(event.poolId.equals(poolPDA));
+    assert.isTrue(event.isToken0Input);
+    assert.isTrue(event.baseInput);
+    assert.equal(event.amountIn.toString(), amountIn.toString());
+    assert(event.tradeFee.gtn(0), "Trade fee should be recorded");
+    assert(event.reserve0After.gt(event.reserve0Before), "Reserve0 should grow");
+    assert(event.reserve1After.lt(event.reserve1Before), "Reserve1 should shrink");
+  });
+
+  it("reports a TWAP over the pool's history", async () => {
+    const twap = await program.methods
+      .observe(new anchor.BN(1))
+      .accounts({ poolState: poolPDA } as any)
+      .view();
+
+    console.log("TWAP window:", twap.window.toString());
+    assert(twap.window.toNumber() >= 1, "Window should cover the request");
+    assert(twap.token0PriceX32.gtn(0), "token0 price should be positive");
+    assert(twap.token1PriceX32.gtn(0), "token1 price should be positive");
+  });
+
+  it("charges per-pool fee overrides instead of the config rates", async () => {
+    const emptyOverrides = {
+      tradeFeeRate: null,
+      protocolFeeRate: null,
+      fundFeeRate: null,
+      creatorFeeRate: null,
+    };
+    await program.methods
+      .updatePoolFees({
+        ...emptyOverrides,
+        tradeFeeRate: new anchor.BN(10000), // 1%
+        protocolFeeRate: new anchor.BN(500000), // 50% of the trade fee
+      })
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        poolState: poolPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const poolBefore = await program.account.poolState.fetch(poolPDA);
+    await program.methods
+      .swap(new anchor.BN(1_000_000), new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: token0Mint,
+        outputTokenMint: token1Mint,
+        inputVault: vault0Pda,
+        outputVault: vault1Pda,
+        inputTokenAccount: userToken0Ata,
+        outputTokenAccount: userToken1Ata,
+        authority: authorityPda,
+        inputTokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+    const poolAfter = await program.account.poolState.fetch(poolPDA);
+
+    // 1% of 1_000_000 is 10_000, half of which goes to the protocol
+    assert.equal(
+      poolAfter.protocolToken0Fee.sub(poolBefore.protocolToken0Fee).toNumber(),
+      5000
+    );
+
+    // Clearing the overrides falls back to the config
+    await program.methods
+      .updatePoolFees(emptyOverrides)
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        poolState: poolPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+    const pool = await program.account.poolState.fetch(poolPDA);
+    assert.isNull(pool.tradeFeeRateOverride);
+    assert.isNull(pool.protocolFeeRateOverride);
+  });
+
+  it("rejects per-pool fee overrides above 100%", async () => {
+    try {
+      await program.methods
+        .updatePoolFees({
+          tradeFeeRate: null,
+          protocolFeeRate: new anchor.BN(800000),
+          fundFeeRate: new anchor.BN(300000),
+          creatorFeeRate: null,
+        })
+        .accounts({
+          owner: owner.publicKey,
+          ammConfig: configPDA,
+          poolState: poolPDA,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Should have rejected fee rates above 100%");
+    } catch (err: any) {
+      assert.include(err.toString(), "FeeExceedHundredPercentage");
+    }
+  });
+
+  it("rejects swaps once a config change makes the overrides invalid", async () => {
+    const configParams = {
+      tradeFeeRate: null,
+      protocolFeeRate: null,
+      fundFeeRate: null,
+      creatorFeeRate: null,
+      createPoolFee: null,
+      protocolOwner: null,
+      fundOwner: null,
+      disableCreatePool: null,
+      dynamicFeeEnabled: null,
+      minTradeFeeRate: null,
+      maxTradeFeeRate: null,
+      volatilityDecayPeriod: null,
+      volatilityFeeFactor: null,
+    };
+    const setPoolFees = (protocolFeeRate: anchor.BN | null) =>
+      program.methods
+        .updatePoolFees({
+          tradeFeeRate: null,
+          protocolFeeRate,
+          fundFeeRate: null,
+          creatorFeeRate: null,
+        })
+        .accounts({
+          owner: owner.publicKey,
+          ammConfig: configPDA,
+          poolState: poolPDA,
+        } as any)
+        .signers([owner])
+        .rpc();
+    const setConfigFundFee = (fundFeeRate: number) =>
+      program.methods
+        .updateAmmConfig({
+          ...configParams,
+          fundFeeRate: new anchor.BN(fundFeeRate),
+        })
+        .accounts({ owner: owner.publicKey, ammConfig: configPDA } as any)
+        .signers([owner])
+        .rpc();
+
+    // 70% protocol override + 25% config fund fee is valid when set...
+    await setPoolFees(new anchor.BN(700000));
+    // ...but not once the config's fund fee goes up to 40%
+    await setConfigFundFee(400000);
+
+    try {
+      await program.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(1), false)
+        .accounts({
+          signer: owner.publicKey,
+          poolState: poolPDA,
+          ammConfig: configPDA,
+          inputTokenMint: token0Mint,
+          outputTokenMint: token1Mint,
+          inputVault: vault0Pda,
+          outputVault: vault1Pda,
+          inputTokenAccount: userToken0Ata,
+          outputTokenAccount: userToken1Ata,
+          authority: authorityPda,
+          inputTokenProgram: TOKEN_PROGRAM_ID,
+          outputTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Swap should fail with invalid overrides");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidPoolFeeRates");
+    } finally {
+      await setConfigFundFee(250000);
+      await setPoolFees(null);
+    }
+  });
+
+  it("blocks swaps on every pool of a paused config", async () => {
+    const swapBit = 1 << 2; // PoolStatusBitIndex::Swap
+
+    await program.methods
+      .updateConfigStatus(swapBit)
+      .accounts({ owner: owner.publicKey, ammConfig: configPDA } as any)
+      .signers([owner])
+      .rpc();
+
+    try {
+      await program.methods
+        .swap(new anchor.BN(1_000_000), new anchor.BN(1), false)
+        .accounts({
+          signer: owner.publicKey,
+          poolState: poolPDA,
+          ammConfig: configPDA,
+          inputTokenMint: token0Mint,
+          outputTokenMint: token1Mint,
+          inputVault: vault0Pda,
+          outputVault: vault1Pda,
+          inputTokenAccount: userToken0Ata,
+          outputTokenAccount: userToken1Ata,
+          authority: authorityPda,
+          inputTokenProgram: TOKEN_PROGRAM_ID,
+          outputTokenProgram: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Swap should be blocked by the config");
+    } catch (err: any) {
+      assert.include(err.toString(), "SwapBlocked");
+    } finally {
+      await program.methods
+        .updateConfigStatus(0)
+        .accounts({ owner: owner.publicKey, ammConfig: configPDA } as any)
+        .signers([owner])
+        .rpc();
+    }
+
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert.equal(config.status, 0);
+  });
 });
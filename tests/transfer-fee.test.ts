@@ -0,0 +1,276 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import {
+  Keypair,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  sendAndConfirmTransaction,
+} from "@solana/web3.js";
+import {
+  ensureAdminState,
+  get_amm_config_pda,
+  get_authority_pda,
+  get_pool_pda,
+  get_vault_pda,
+  orderMints,
+} from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import {
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  ExtensionType,
+  getAccount,
+  getMintLen,
+  getOrCreateAssociatedTokenAccount,
+  mintTo,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+} from "@solana/spl-token";
+import { assert } from "chai";
+
+describe("transfer-fee mints", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const provider = anchor.getProvider() as anchor.AnchorProvider;
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  const feeBasisPoints = 100; // 1% transfer fee
+  const maxFee = BigInt(1_000_000_000);
+
+  let configPDA: PublicKey;
+  let poolPDA: PublicKey;
+  let feeMint: PublicKey;
+  let plainMint: PublicKey;
+  let token0Mint: PublicKey;
+  let token1Mint: PublicKey;
+  let token0Program: PublicKey;
+  let token1Program: PublicKey;
+  let userToken0Ata: PublicKey;
+  let userToken1Ata: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 7000) % 65535;
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500),
+        new anchor.BN(0),
+        new anchor.BN(100000),
+        new anchor.BN(250000),
+        new anchor.BN(0)
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+
+    // 1. Token-2022 mint with a TransferFee extension
+    const feeMintKeypair = Keypair.generate();
+    feeMint = feeMintKeypair.publicKey;
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+    const lamports =
+      await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+    await sendAndConfirmTransaction(
+      provider.connection,
+      new Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: owner.publicKey,
+          newAccountPubkey: feeMint,
+          space: mintLen,
+          lamports,
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint,
+          owner.publicKey,
+          owner.publicKey,
+          feeBasisPoints,
+          maxFee,
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint,
+          9,
+          owner.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [owner, feeMintKeypair]
+    );
+
+    // 2. Plain legacy mint for the other side
+    plainMint = await createMint(
+      provider.connection,
+      owner,
+      owner.publicKey,
+      null,
+      9
+    );
+
+    [token0Mint, token1Mint] = orderMints(feeMint, plainMint);
+    token0Program = token0Mint.equals(feeMint)
+      ? TOKEN_2022_PROGRAM_ID
+      : TOKEN_PROGRAM_ID;
+    token1Program = token1Mint.equals(feeMint)
+      ? TOKEN_2022_PROGRAM_ID
+      : TOKEN_PROGRAM_ID;
+
+    userToken0Ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        owner,
+        token0Mint,
+        owner.publicKey,
+        false,
+        undefined,
+        undefined,
+        token0Program
+      )
+    ).address;
+    userToken1Ata = (
+      await getOrCreateAssociatedTokenAccount(
+        provider.connection,
+        owner,
+        token1Mint,
+        owner.publicKey,
+        false,
+        undefined,
+        undefined,
+        token1Program
+      )
+    ).address;
+
+    await mintTo(
+      provider.connection,
+      owner,
+      token0Mint,
+      userToken0Ata,
+      owner,
+      100_000_000_000,
+      [],
+      undefined,
+      token0Program
+    );
+    await mintTo(
+      provider.connection,
+      owner,
+      token1Mint,
+      userToken1Ata,
+      owner,
+      100_000_000_000,
+      [],
+      undefined,
+      token1Program
+    );
+
+    poolPDA = get_pool_pda({
+      program_id: program.programId,
+      config_pda: configPDA,
+      mint0: token0Mint,
+      mint1: token1Mint,
+    });
+
+    await program.methods
+      .createPool(
+        configIndex,
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(10_000_000_000),
+        new anchor.BN(0),
+        { bothToken: {} }
+      )
+      .accounts({
+        creator: owner.publicKey,
+        ammConfig: configPDA,
+        token0Mint,
+        token1Mint,
+        creatorToken0: userToken0Ata,
+        creatorToken1: userToken1Ata,
+        feeReceiver: owner.publicKey,
+        token0Program,
+        token1Program,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    // Wait for pool to open
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+  });
+
+  it("applies the input transfer fee before pricing the swap", async () => {
+    const feeMintIsToken0 = token0Mint.equals(feeMint);
+    const [inputMint, outputMint] = feeMintIsToken0
+      ? [token0Mint, token1Mint]
+      : [token1Mint, token0Mint];
+    const [inputProgram, outputProgram] = feeMintIsToken0
+      ? [token0Program, token1Program]
+      : [token1Program, token0Program];
+    const [inputAta, outputAta] = feeMintIsToken0
+      ? [userToken0Ata, userToken1Ata]
+      : [userToken1Ata, userToken0Ata];
+    const inputVault = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: inputMint,
+    });
+    const outputVault = get_vault_pda({
+      program_id: program.programId,
+      pool: poolPDA,
+      mint: outputMint,
+    });
+
+    const amountIn = BigInt(1_000_000_000);
+    const expectedTransferFee = (amountIn * BigInt(feeBasisPoints)) / 10_000n;
+
+    const vaultBefore = await getAccount(
+      provider.connection,
+      inputVault,
+      undefined,
+      inputProgram
+    );
+
+    await program.methods
+      .swap(new anchor.BN(amountIn.toString()), new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        inputTokenMint: inputMint,
+        outputTokenMint: outputMint,
+        inputVault,
+        outputVault,
+        inputTokenAccount: inputAta,
+        outputTokenAccount: outputAta,
+        authority: get_authority_pda({ program_id: program.programId }),
+        inputTokenProgram: inputProgram,
+        outputTokenProgram: outputProgram,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const vaultAfter = await getAccount(
+      provider.connection,
+      inputVault,
+      undefined,
+      inputProgram
+    );
+    assert.equal(
+      (vaultAfter.amount - vaultBefore.amount).toString(),
+      (amountIn - expectedTransferFee).toString(),
+      "Vault should receive amount_in minus the transfer fee"
+    );
+  });
+});
@@ -0,0 +1,147 @@
+import * as anchor from "@coral-xyz/anchor";
+import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
+import { PublicKey, Keypair } from "@solana/web3.js";
+import { ensureAdminState, get_amm_config_pda } from "./utils";
+import { SYSTEM_PROGRAM_ID } from "@coral-xyz/anchor/dist/cjs/native/system";
+import { assert } from "chai";
+
+describe("update_amm_config", () => {
+  anchor.setProvider(anchor.AnchorProvider.env());
+  const program = anchor.workspace.VeerbalCpmm as anchor.Program<VeerbalCpmm>;
+  const owner = anchor.Wallet.local().payer;
+
+  let configPDA: PublicKey;
+  // Unique index per run (for devnet compatibility)
+  const configIndex = (Math.floor(Date.now() / 1000) + 6000) % 65535;
+
+  const emptyParams = {
+    tradeFeeRate: null,
+    protocolFeeRate: null,
+    fundFeeRate: null,
+    creatorFeeRate: null,
+    createPoolFee: null,
+    protocolOwner: null,
+    fundOwner: null,
+    disableCreatePool: null,
+    dynamicFeeEnabled: null,
+    minTradeFeeRate: null,
+    maxTradeFeeRate: null,
+    volatilityDecayPeriod: null,
+    volatilityFeeFactor: null,
+  };
+
+  before(async () => {
+    await ensureAdminState(program, owner);
+
+    configPDA = get_amm_config_pda({
+      index: configIndex,
+      program_id: program.programId,
+    });
+
+    await program.methods
+      .createConfig(
+        configIndex,
+        new anchor.BN(2500), // trade_fee_rate
+        new anchor.BN(0), // creator_fee_rate
+        new anchor.BN(100000), // protocol_fee_rate
+        new anchor.BN(250000), // fund_fee_rate
+        new anchor.BN(0) // create_pool_fee
+      )
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+        systemProgram: SYSTEM_PROGRAM_ID,
+      })
+      .signers([owner])
+      .rpc();
+  });
+
+  it("updates only the provided fields", async () => {
+    const newFundOwner = Keypair.generate().publicKey;
+
+    await program.methods
+      .updateAmmConfig({
+        ...emptyParams,
+        tradeFeeRate: new anchor.BN(3000),
+        fundOwner: newFundOwner,
+        disableCreatePool: true,
+      })
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert.equal(config.tradeFeeRate.toNumber(), 3000);
+    assert.equal(config.protocolFeeRate.toNumber(), 100000);
+    assert.equal(config.fundOwner.toBase58(), newFundOwner.toBase58());
+    assert.equal(config.protocolOwner.toBase58(), owner.publicKey.toBase58());
+    assert.isTrue(config.disableCreatePool);
+  });
+
+  it("rejects fee rates above 100%", async () => {
+    try {
+      await program.methods
+        .updateAmmConfig({
+          ...emptyParams,
+          protocolFeeRate: new anchor.BN(800000),
+          fundFeeRate: new anchor.BN(300000),
+        })
+        .accounts({
+          owner: owner.publicKey,
+          ammConfig: configPDA,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Should have rejected fee rates above 100%");
+    } catch (err: any) {
+      assert.include(err.toString(), "FeeExceedHundredPercentage");
+    }
+  });
+
+  it("enables the dynamic trade fee", async () => {
+    await program.methods
+      .updateAmmConfig({
+        ...emptyParams,
+        dynamicFeeEnabled: true,
+        minTradeFeeRate: new anchor.BN(1000),
+        maxTradeFeeRate: new anchor.BN(20000),
+        volatilityDecayPeriod: new anchor.BN(300),
+        volatilityFeeFactor: new anchor.BN(500000),
+      })
+      .accounts({
+        owner: owner.publicKey,
+        ammConfig: configPDA,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const config = await program.account.ammConfig.fetch(configPDA);
+    assert.isTrue(config.dynamicFeeEnabled);
+    assert.equal(config.minTradeFeeRate.toNumber(), 1000);
+    assert.equal(config.maxTradeFeeRate.toNumber(), 20000);
+    assert.equal(config.volatilityDecayPeriod.toNumber(), 300);
+    assert.equal(config.volatilityFeeFactor.toNumber(), 500000);
+  });
+
+  it("rejects a base trade fee outside the dynamic bounds", async () => {
+    try {
+      await program.methods
+        .updateAmmConfig({
+          ...emptyParams,
+          tradeFeeRate: new anchor.BN(30000),
+        })
+        .accounts({
+          owner: owner.publicKey,
+          ammConfig: configPDA,
+        } as any)
+        .signers([owner])
+        .rpc();
+      assert.fail("Should have rejected a base rate above the maximum");
+    } catch (err: any) {
+      assert.include(err.toString(), "InvalidDynamicFee");
+    }
+  });
+});
@@ -0,0 +1,24 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Keypair } from "@solana/web3.js";
+import { VeerbalCpmm } from "../../target/types/veerbal_cpmm";
+import { get_admin_state_pda } from "./pda";
+
+// Admin gated instructions read the admin from this singleton, create it once per cluster
+export const ensureAdminState = async (
+  program: anchor.Program<VeerbalCpmm>,
+  owner: Keypair
+) => {
+  const adminState = get_admin_state_pda({ program_id: program.programId });
+  const existing = await program.provider.connection.getAccountInfo(adminState);
+  if (existing) {
+    return adminState;
+  }
+
+  await program.methods
+    .initializeAdmin()
+    .accounts({ owner: owner.publicKey, adminState } as any)
+    .signers([owner])
+    .rpc();
+
+  return adminState;
+};
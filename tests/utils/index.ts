@@ -1 +1,2 @@
 export * from "./pda";
+export * from "./admin";
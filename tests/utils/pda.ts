@@ -95,3 +95,93 @@ export const orderMints = (
     ? [mintA, mintB]
     : [mintB, mintA];
 };
+
+export const get_admin_state_pda = ({
+  program_id,
+}: {
+  program_id: PublicKey;
+}) => {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("ADMIN")],
+    program_id
+  );
+
+  return pda;
+};
+
+export const get_locked_lp_vault_pda = ({
+  program_id,
+  pool,
+}: {
+  program_id: PublicKey;
+  pool: PublicKey;
+}) => {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("LOCKED_LP_VAULT"), pool.toBuffer()],
+    program_id
+  );
+
+  return pda;
+};
+
+export const get_locked_position_pda = ({
+  program_id,
+  pool,
+  owner,
+}: {
+  program_id: PublicKey;
+  pool: PublicKey;
+  owner: PublicKey;
+}) => {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("LOCKED_POSITION"), pool.toBuffer(), owner.toBuffer()],
+    program_id
+  );
+
+  return pda;
+};
+
+export const get_lp_vesting_pda = ({
+  program_id,
+  pool,
+  beneficiary,
+  funder,
+  nonce,
+}: {
+  program_id: PublicKey;
+  pool: PublicKey;
+  beneficiary: PublicKey;
+  funder: PublicKey;
+  nonce: number;
+}) => {
+  const nonce_buffer = Buffer.alloc(8);
+  nonce_buffer.writeBigUInt64BE(BigInt(nonce));
+
+  const [pda] = PublicKey.findProgramAddressSync(
+    [
+      Buffer.from("LP_VESTING"),
+      pool.toBuffer(),
+      beneficiary.toBuffer(),
+      funder.toBuffer(),
+      nonce_buffer,
+    ],
+    program_id
+  );
+
+  return pda;
+};
+
+export const get_lp_vesting_vault_pda = ({
+  program_id,
+  lp_vesting,
+}: {
+  program_id: PublicKey;
+  lp_vesting: PublicKey;
+}) => {
+  const [pda] = PublicKey.findProgramAddressSync(
+    [Buffer.from("LP_VESTING_VAULT"), lp_vesting.toBuffer()],
+    program_id
+  );
+
+  return pda;
+};
@@ -2,6 +2,7 @@ import * as anchor from "@coral-xyz/anchor";
 import { VeerbalCpmm } from "../target/types/veerbal_cpmm";
 import { PublicKey } from "@solana/web3.js";
 import {
+  ensureAdminState,
   get_amm_config_pda,
   get_pool_pda,
   get_vault_pda,
@@ -41,6 +42,8 @@ describe("withdraw", () => {
   const configIndex = (Math.floor(Date.now() / 1000) + 3000) % 65535;
 
   before(async () => {
+    await ensureAdminState(program, owner);
+
     // 1. Create config
     configPDA = get_amm_config_pda({
       index: configIndex,
@@ -144,7 +147,8 @@ describe("withdraw", () => {
         configIndex,
         new anchor.BN(10_000_000_000), // 10B initial
         new anchor.BN(10_000_000_000),
-        new anchor.BN(0)
+        new anchor.BN(0),
+        { bothToken: {} }
       )
       .accounts({
         creator: owner.publicKey,
@@ -154,6 +158,8 @@ describe("withdraw", () => {
         creatorToken0: creatorToken0Ata,
         creatorToken1: creatorToken1Ata,
         feeReceiver: owner.publicKey,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -178,7 +184,7 @@ describe("withdraw", () => {
     const minToken1 = new anchor.BN(1);
 
     await program.methods
-      .withdraw(lpToWithdraw, minToken0, minToken1)
+      .withdraw(lpToWithdraw, minToken0, minToken1, false)
       .accounts({
         signer: owner.publicKey,
         poolState: poolPDA,
@@ -193,6 +199,8 @@ describe("withdraw", () => {
         lpMint: lpMintPda,
         signerLp: creatorLpAta,
         tokenProgram: TOKEN_PROGRAM_ID,
+        token0Program: TOKEN_PROGRAM_ID,
+        token1Program: TOKEN_PROGRAM_ID,
       } as any)
       .signers([owner])
       .rpc();
@@ -218,4 +226,119 @@ describe("withdraw", () => {
 
     console.log("Withdraw successful!");
   });
+
+  it("withdraws to a single token", async () => {
+    const lpBalanceBefore = await getAccount(provider.connection, creatorLpAta);
+    const userToken0Before = await getAccount(provider.connection, creatorToken0Ata);
+    const userToken1Before = await getAccount(provider.connection, creatorToken1Ata);
+    const vault0Before = await getAccount(provider.connection, vault0Pda);
+
+    const lpToWithdraw = new anchor.BN(Number(lpBalanceBefore.amount) / 4);
+
+    await program.methods
+      .withdrawSingleToken(lpToWithdraw, new anchor.BN(1), false)
+      .accounts({
+        signer: owner.publicKey,
+        poolState: poolPDA,
+        ammConfig: configPDA,
+        outputTokenMint: token1Mint,
+        outputTokenAccount: creatorToken1Ata,
+        outputVault: vault1Pda,
+        inputVault: vault0Pda,
+        signerLp: creatorLpAta,
+        lpMint: lpMintPda,
+        authority: authorityPda,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        outputTokenProgram: TOKEN_PROGRAM_ID,
+      } as any)
+      .signers([owner])
+      .rpc();
+
+    const lpBalanceAfter = await getAccount(provider.connection, creatorLpAta);
+    const userToken0After = await getAccount(provider.connection, creatorToken0Ata);
+    const userToken1After = await getAccount(provider.connection, creatorToken1Ata);
+    const vault0After = await getAccount(provider.connection, vault0Pda);
+
+    assert.equal(
+      (lpBalanceBefore.amount - lpBalanceAfter.amount).toString(),
+      lpToWithdraw.toString(),
+      "Should burn the requested LP"
+    );
+    assert(userToken1After.amount > userToken1Before.amount, "Should receive token1");
+    assert.equal(userToken0After.amount, userToken0Before.amount, "Token0 should be untouched");
+    assert.equal(vault0After.amount, vault0Before.amount, "Token0 share stays in the pool");
+  });
+
+  it("keeps withdrawals open under a global trading pause", async () => {
+    const depositBit = 1 << 0; // PoolStatusBitIndex::Deposit
+    const swapBit = 1 << 2; // PoolStatusBitIndex::Swap
+
+    await program.methods
+      .updateGlobalStatus(depositBit | swapBit)
+      .accounts({ owner: owner.publicKey } as any)
+      .signers([owner])
+      .rpc();
+
+    try {
+      const lpBalance = await getAccount(provider.connection, creatorLpAta);
+      const lpToWithdraw = new anchor.BN((lpBalance.amount / BigInt(10)).toString());
+
+      // The zap out swaps against the pool, so it is frozen too
+      try {
+        await program.methods
+          .withdrawSingleToken(lpToWithdraw, new anchor.BN(1), false)
+          .accounts({
+            signer: owner.publicKey,
+            poolState: poolPDA,
+            ammConfig: configPDA,
+            outputTokenMint: token1Mint,
+            outputTokenAccount: creatorToken1Ata,
+            outputVault: vault1Pda,
+            inputVault: vault0Pda,
+            signerLp: creatorLpAta,
+            lpMint: lpMintPda,
+            authority: authorityPda,
+            tokenProgram: TOKEN_PROGRAM_ID,
+            outputTokenProgram: TOKEN_PROGRAM_ID,
+          } as any)
+          .signers([owner])
+          .rpc();
+        assert.fail("Zap out should be blocked");
+      } catch (err: any) {
+        assert.include(err.toString(), "SwapBlocked");
+      }
+
+      await program.methods
+        .withdraw(lpToWithdraw, new anchor.BN(1), new anchor.BN(1), false)
+        .accounts({
+          signer: owner.publicKey,
+          poolState: poolPDA,
+          ammConfig: configPDA,
+          authority: authorityPda,
+          token0Mint: token0Mint,
+          token1Mint: token1Mint,
+          signerToken0: creatorToken0Ata,
+          signerToken1: creatorToken1Ata,
+          token0Vault: vault0Pda,
+          token1Vault: vault1Pda,
+          lpMint: lpMintPda,
+          signerLp: creatorLpAta,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          token0Program: TOKEN_PROGRAM_ID,
+          token1Program: TOKEN_PROGRAM_ID,
+        } as any)
+        .signers([owner])
+        .rpc();
+
+      const lpAfter = await getAccount(provider.connection, creatorLpAta);
+      assert(lpAfter.amount < lpBalance.amount, "Plain withdrawals stay allowed");
+    } finally {
+      // Never leave the other suites paused
+      await program.methods
+        .updateGlobalStatus(0)
+        .accounts({ owner: owner.publicKey } as any)
+        .signers([owner])
+        .rpc();
+    }
+  });
 });